@@ -1,3 +1,58 @@
+// Every command the app registers; tauri-build generates an allow-<command> permission for each,
+// so a capability only reaches the commands it lists (see capabilities/ and permissions/)
+const COMMANDS: &[&str] = &[
+  "get_users",
+  "create_user",
+  "update_user",
+  "get_game_modes",
+  "create_game_mode",
+  "update_game_mode",
+  "archive_game_mode",
+  "delete_game_mode",
+  "ensure_basic_game_mode",
+  "start_match",
+  "add_point",
+  "record_let",
+  "call_timeout",
+  "forfeit_match",
+  "undo_last_point",
+  "redo_point",
+  "rebuild_match",
+  "pause_match",
+  "resume_match",
+  "edit_match_result",
+  "get_match_audit",
+  "get_match",
+  "get_user_matches",
+  "set_first_server",
+  "cancel_match",
+  "get_open_matches",
+  "get_user_statistics",
+  "suggest_handicap",
+  "create_tournament",
+  "get_tournaments",
+  "register_tournament_player",
+  "start_tournament",
+  "get_bracket",
+  "start_next_bracket_match",
+  "create_league",
+  "get_leagues",
+  "register_league_player",
+  "start_league",
+  "get_league",
+  "start_league_fixture",
+  "get_key_bindings",
+  "set_key_binding",
+  "delete_key_binding",
+  "reset_key_bindings",
+  "get_setting",
+  "set_setting",
+  "open_scoreboard_window",
+];
+
 fn main() {
-  tauri_build::build()
+  tauri_build::try_build(
+    tauri_build::Attributes::new().app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+  )
+  .expect("failed to run tauri-build")
 }
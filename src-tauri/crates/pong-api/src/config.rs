@@ -0,0 +1,80 @@
+// API settings, read from the `settings` table when the app starts:
+//   api_enabled  "true" to start the server (off by default)
+//   api_bind     "localhost" (default) or "lan" to listen on every interface
+//   api_port     defaults to DEFAULT_PORT
+//   api_token    required; requests must send `Authorization: Bearer <token>`
+// The live scoreboard feed (/api/live) is served by the same server, so spectators
+// need api_enabled and api_token set too.
+
+use pong_core::{AppError, Repository};
+use std::net::{IpAddr, Ipv4Addr};
+
+pub const ENABLED_KEY: &str = "api_enabled";
+pub const BIND_KEY: &str = "api_bind";
+pub const PORT_KEY: &str = "api_port";
+pub const TOKEN_KEY: &str = "api_token";
+
+pub const DEFAULT_PORT: u16 = 7878;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub bind: IpAddr,
+    pub port: u16,
+    pub token: Option<String>,
+}
+
+impl ApiConfig {
+    pub async fn load(repo: &Repository) -> Result<Self, AppError> {
+        let enabled = repo.get_setting(ENABLED_KEY).await?;
+        let bind = repo.get_setting(BIND_KEY).await?;
+        let port = repo.get_setting(PORT_KEY).await?;
+        let token = repo.get_setting(TOKEN_KEY).await?;
+        Self::from_settings(enabled.as_deref(), bind.as_deref(), port.as_deref(), token.as_deref())
+    }
+
+    pub fn from_settings(
+        enabled: Option<&str>,
+        bind: Option<&str>,
+        port: Option<&str>,
+        token: Option<&str>,
+    ) -> Result<Self, AppError> {
+        let bind = match bind.unwrap_or("localhost") {
+            "localhost" => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            "lan" => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            other => return Err(AppError::validation(format!("{} must be localhost or lan, got {}", BIND_KEY, other))),
+        };
+        let port = match port {
+            Some(value) => value
+                .parse()
+                .map_err(|_| AppError::validation(format!("{} must be a port number, got {}", PORT_KEY, value)))?,
+            None => DEFAULT_PORT,
+        };
+        Ok(Self {
+            enabled: matches!(enabled, Some("true") | Some("1")),
+            bind,
+            port,
+            token: token.map(str::trim).filter(|t| !t.is_empty()).map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_are_parsed() {
+        let config = ApiConfig::from_settings(None, None, None, None).unwrap();
+        assert!(!config.enabled);
+        assert_eq!((config.bind, config.port, config.token), (IpAddr::V4(Ipv4Addr::LOCALHOST), DEFAULT_PORT, None));
+
+        let config = ApiConfig::from_settings(Some("true"), Some("lan"), Some("9000"), Some(" secret ")).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.bind, IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        assert_eq!((config.port, config.token.as_deref()), (9000, Some("secret")));
+
+        assert!(ApiConfig::from_settings(None, Some("everywhere"), None, None).is_err());
+        assert!(ApiConfig::from_settings(None, None, Some("http"), None).is_err());
+    }
+}
@@ -0,0 +1,20 @@
+// Opt-in local HTTP API, so office tools can read results and score without Tauri `invoke`.
+// Everything goes through pong-core's Repository, exactly like the Tauri commands.
+
+pub mod config;
+mod live;
+mod routes;
+
+pub use config::ApiConfig;
+pub use routes::router;
+
+use pong_core::Repository;
+use std::net::SocketAddr;
+
+// Serves until the listener fails; the app spawns this once at startup
+pub async fn serve(repo: Repository, config: ApiConfig, token: String) -> std::io::Result<()> {
+    let addr = SocketAddr::new(config.bind, config.port);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    log::info!("REST API listening on http://{}", addr);
+    axum::serve(listener, router(repo, token)).await
+}
@@ -0,0 +1,78 @@
+// WebSocket scoreboard feed: every message is a PopulatedMatch as JSON.
+// On connect the client gets the current state of the open matches (or of ?match=<id>),
+// then one message per change. Browsers cannot set headers on a WebSocket, so ?token= works too.
+// It has no setting of its own: it is only up when the REST API is enabled with a token.
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::Response;
+use pong_core::models::PopulatedMatch;
+use pong_core::{AppError, Repository};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::routes::{ApiError, ApiState};
+
+#[derive(Deserialize)]
+pub(crate) struct LiveQuery {
+    #[serde(rename = "match")]
+    match_id: Option<i64>,
+}
+
+pub(crate) async fn live(
+    State(state): State<ApiState>,
+    Query(query): Query<LiveQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    // Subscribe before reading the snapshot so no change falls in between
+    let feed = state.repo.live().subscribe();
+    let snapshot = initial_state(&state.repo, query.match_id).await?;
+    Ok(upgrade.on_upgrade(move |socket| stream(socket, feed, snapshot, query.match_id)))
+}
+
+async fn initial_state(repo: &Repository, match_id: Option<i64>) -> Result<Vec<PopulatedMatch>, AppError> {
+    match match_id {
+        Some(id) => Ok(vec![repo.get_match(id).await?]),
+        None => repo.get_open_matches().await,
+    }
+}
+
+async fn send(socket: &mut WebSocket, m: &PopulatedMatch) -> bool {
+    match serde_json::to_string(m) {
+        Ok(json) => socket.send(Message::Text(json.into())).await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+async fn stream(
+    mut socket: WebSocket,
+    mut feed: tokio::sync::broadcast::Receiver<std::sync::Arc<PopulatedMatch>>,
+    snapshot: Vec<PopulatedMatch>,
+    match_id: Option<i64>,
+) {
+    for m in &snapshot {
+        if !send(&mut socket, m).await {
+            return;
+        }
+    }
+    loop {
+        tokio::select! {
+            update = feed.recv() => match update {
+                Ok(m) if match_id.map_or(true, |id| id == m.id) => {
+                    if !send(&mut socket, &m).await {
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                // Older updates were dropped, the next one carries the full state anyway
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return,
+            },
+            // Spectators only listen; anything but a close is ignored
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
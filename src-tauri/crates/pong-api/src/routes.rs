@@ -0,0 +1,163 @@
+// Routes and handlers. Errors are the same { code, message } body the frontend gets,
+// with an HTTP status picked from the code.
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use pong_core::models::{GameMode, MatchEventType, PopulatedMatch, User, UserStatistics};
+use pong_core::{AppError, Repository};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::live;
+
+const DEFAULT_MATCH_LIMIT: i64 = 50;
+const MAX_MATCH_LIMIT: i64 = 200;
+
+#[derive(Clone)]
+pub(crate) struct ApiState {
+    pub(crate) repo: Repository,
+    token: Arc<str>,
+}
+
+pub(crate) struct ApiError(AppError);
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        ApiError(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0 {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation { .. } => StatusCode::BAD_REQUEST,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::RuleViolation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self.0)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+pub fn router(repo: Repository, token: String) -> Router {
+    let state = ApiState { repo, token: token.into() };
+    Router::new()
+        .route("/api/users", get(users))
+        .route("/api/users/{id}/statistics", get(user_statistics))
+        .route("/api/leaderboard", get(leaderboard))
+        .route("/api/game-modes", get(game_modes))
+        .route("/api/matches", get(matches))
+        .route("/api/matches/open", get(open_matches))
+        .route("/api/matches/{id}", get(match_by_id))
+        .route("/api/matches/{id}/points", post(add_point))
+        .route("/api/matches/{id}/undo", post(undo))
+        .route("/api/live", get(live::live))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+// Compares every byte so the response time does not leak how much of the token matched
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected.bytes().zip(given.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+// The token travels in the Authorization header, or in ?token= for WebSocket clients
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let given = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or_else(|| Query::<TokenQuery>::try_from_uri(request.uri()).ok().and_then(|Query(query)| query.token));
+    match given {
+        Some(token) if token_matches(&state.token, &token) => next.run(request).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({ "code": "UNAUTHORIZED", "message": "Missing or invalid API token" })),
+        )
+            .into_response(),
+    }
+}
+
+async fn users(State(state): State<ApiState>) -> ApiResult<Vec<User>> {
+    Ok(Json(state.repo.get_users().await?))
+}
+
+async fn user_statistics(State(state): State<ApiState>, Path(id): Path<i64>) -> ApiResult<UserStatistics> {
+    Ok(Json(state.repo.get_user_statistics(id).await?))
+}
+
+async fn leaderboard(State(state): State<ApiState>) -> ApiResult<Vec<User>> {
+    Ok(Json(state.repo.get_leaderboard().await?))
+}
+
+async fn game_modes(State(state): State<ApiState>) -> ApiResult<Vec<GameMode>> {
+    Ok(Json(state.repo.get_game_modes().await?))
+}
+
+#[derive(Deserialize)]
+struct MatchesQuery {
+    status: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn matches(State(state): State<ApiState>, Query(query): Query<MatchesQuery>) -> ApiResult<Vec<PopulatedMatch>> {
+    let limit = query.limit.unwrap_or(DEFAULT_MATCH_LIMIT).clamp(1, MAX_MATCH_LIMIT);
+    Ok(Json(state.repo.list_matches(query.status.as_deref(), limit).await?))
+}
+
+async fn open_matches(State(state): State<ApiState>) -> ApiResult<Vec<PopulatedMatch>> {
+    Ok(Json(state.repo.get_open_matches().await?))
+}
+
+async fn match_by_id(State(state): State<ApiState>, Path(id): Path<i64>) -> ApiResult<PopulatedMatch> {
+    Ok(Json(state.repo.get_match(id).await?))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PointBody {
+    player_id: i64,
+    reason: Option<MatchEventType>,
+    idempotency_key: Option<String>,
+    expected_version: Option<i64>,
+}
+
+async fn add_point(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    Json(body): Json<PointBody>,
+) -> ApiResult<PopulatedMatch> {
+    let m = state.repo.add_point(id, body.player_id, body.reason, body.idempotency_key.as_deref(), body.expected_version).await?;
+    Ok(Json(m))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct UndoBody {
+    idempotency_key: Option<String>,
+    expected_version: Option<i64>,
+}
+
+async fn undo(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    body: Option<Json<UndoBody>>,
+) -> ApiResult<PopulatedMatch> {
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+    Ok(Json(state.repo.undo_last_point(id, body.idempotency_key.as_deref(), body.expected_version).await?))
+}
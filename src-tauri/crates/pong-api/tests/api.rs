@@ -0,0 +1,128 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use pong_core::models::StartMatchDto;
+use pong_core::{db, Repository};
+use serde_json::{json, Value};
+use futures_util::StreamExt;
+use tokio_tungstenite::tungstenite::Message;
+use tower::ServiceExt;
+
+const TOKEN: &str = "office-secret";
+
+// Alice against Bob on the default mode, Alice serving first
+async fn test_app(name: &str) -> (Router, i64) {
+    let (repo, match_id) = test_repo(name).await;
+    (pong_api::router(repo, TOKEN.to_string()), match_id)
+}
+
+async fn test_repo(name: &str) -> (Repository, i64) {
+    let repo = Repository::new(db::open_in_memory(name).await.unwrap());
+    sqlx::query("INSERT INTO users (name) VALUES ('Alice'), ('Bob')")
+        .execute(repo.pool())
+        .await
+        .unwrap();
+    repo.ensure_basic_game_mode().await.unwrap();
+    let m = repo
+        .start_match(StartMatchDto { player1_id: 1, player2_id: 2, game_mode_id: 1, ..Default::default() }, None)
+        .await
+        .unwrap();
+    repo.set_first_server(m.id, 1, None).await.unwrap();
+    (repo, m.id)
+}
+
+async fn call(app: &Router, method: &str, uri: &str, token: Option<&str>, body: Option<Value>) -> (StatusCode, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let request = match body {
+        Some(body) => request.header(header::CONTENT_TYPE, "application/json").body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    };
+    let response = app.clone().oneshot(request.unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn requests_need_the_token() {
+    let (app, _) = test_app("pong-api-auth").await;
+    assert_eq!(call(&app, "GET", "/api/users", None, None).await.0, StatusCode::UNAUTHORIZED);
+    let (status, body) = call(&app, "GET", "/api/users", Some("guess"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "UNAUTHORIZED");
+
+    let (status, body) = call(&app, "GET", "/api/users", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn query_tokens_are_percent_decoded() {
+    let (repo, _) = test_repo("pong-api-query-token").await;
+    let app = pong_api::router(repo, "a+b&c=d%".to_string());
+    let (status, _) = call(&app, "GET", "/api/users?token=a%2Bb%26c%3Dd%25", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = call(&app, "GET", "/api/users?token=a+b&c=d%", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn match_list_limit_is_clamped() {
+    let (app, _) = test_app("pong-api-limit").await;
+    for limit in ["0", "-5", "1000000"] {
+        let (status, body) = call(&app, "GET", &format!("/api/matches?limit={}", limit), Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn points_are_scored_through_the_api() {
+    let (app, match_id) = test_app("pong-api-points").await;
+    let points = format!("/api/matches/{}/points", match_id);
+
+    let (status, body) = call(&app, "POST", &points, Some(TOKEN), Some(json!({ "playerId": 1, "reason": "ace" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["score"], json!({ "p1": 1, "p2": 0 }));
+
+    let (_, open) = call(&app, "GET", "/api/matches/open", Some(TOKEN), None).await;
+    assert_eq!(open[0]["_id"], match_id);
+
+    let (status, body) = call(&app, "POST", &format!("/api/matches/{}/undo", match_id), Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["score"], json!({ "p1": 0, "p2": 0 }));
+
+    let (status, body) = call(&app, "GET", "/api/matches/99", Some(TOKEN), None).await;
+    assert_eq!((status, body["code"].clone()), (StatusCode::NOT_FOUND, json!("NOT_FOUND")));
+    let (status, _) = call(&app, "POST", &points, Some(TOKEN), Some(json!({ "playerId": 99 }))).await;
+    assert!(status.is_client_error());
+}
+
+type Socket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn next_match(socket: &mut Socket) -> Value {
+    match socket.next().await.unwrap().unwrap() {
+        Message::Text(text) => serde_json::from_str(&text).unwrap(),
+        other => panic!("expected a text message, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn live_feed_pushes_every_change() {
+    let (repo, match_id) = test_repo("pong-api-live").await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = pong_api::router(repo.clone(), TOKEN.to_string());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let url = format!("ws://{}/api/live?match={}&token={}", addr, match_id, TOKEN);
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    assert_eq!(next_match(&mut socket).await["score"], json!({ "p1": 0, "p2": 0 }));
+    repo.add_point(match_id, 2, None, None, None).await.unwrap();
+    assert_eq!(next_match(&mut socket).await["score"], json!({ "p1": 0, "p2": 1 }));
+    repo.cancel_match(match_id, None).await.unwrap();
+    assert_eq!(next_match(&mut socket).await["status"], "abandoned");
+}
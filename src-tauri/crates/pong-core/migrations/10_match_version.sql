@@ -0,0 +1,2 @@
+-- Bumped on every write, used for optimistic concurrency checks
+ALTER TABLE matches ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
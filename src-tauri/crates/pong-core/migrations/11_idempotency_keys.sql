@@ -0,0 +1,11 @@
+-- Client-generated keys of recently applied actions, with the response that was returned
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    key TEXT PRIMARY KEY,
+    command TEXT NOT NULL, -- 'start_match', 'add_point', 'undo_last_point'
+    match_id INTEGER NOT NULL,
+    response TEXT NOT NULL, -- JSON String of the PopulatedMatch
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(match_id) REFERENCES matches(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_match ON idempotency_keys(match_id);
@@ -0,0 +1,13 @@
+-- History of manual corrections to match results
+CREATE TABLE IF NOT EXISTS match_audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    match_id INTEGER NOT NULL,
+    action TEXT NOT NULL, -- 'edit_result'
+    before TEXT NOT NULL, -- JSON String of the result before the change
+    after TEXT NOT NULL, -- JSON String of the result after the change
+    note TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(match_id) REFERENCES matches(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_match_audit_match ON match_audit(match_id);
@@ -0,0 +1,42 @@
+-- Office cups: a single-elimination bracket of singles matches
+CREATE TABLE IF NOT EXISTS tournaments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    format TEXT NOT NULL DEFAULT 'single_elimination',
+    game_mode_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'registration', -- 'registration', 'in_progress', 'finished'
+    winner_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(game_mode_id) REFERENCES game_modes(id),
+    FOREIGN KEY(winner_id) REFERENCES users(id)
+);
+
+CREATE TABLE IF NOT EXISTS tournament_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tournament_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    seed INTEGER, -- 1 is the favourite, set when the bracket is drawn
+    FOREIGN KEY(tournament_id) REFERENCES tournaments(id),
+    FOREIGN KEY(user_id) REFERENCES users(id),
+    UNIQUE(tournament_id, user_id)
+);
+
+-- One row per bracket slot; players are filled in as winners advance
+CREATE TABLE IF NOT EXISTS tournament_matches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tournament_id INTEGER NOT NULL,
+    round INTEGER NOT NULL, -- 1 is the first round
+    position INTEGER NOT NULL, -- 0-based, top to bottom
+    player1_id INTEGER,
+    player2_id INTEGER,
+    match_id INTEGER,
+    winner_id INTEGER,
+    FOREIGN KEY(tournament_id) REFERENCES tournaments(id),
+    FOREIGN KEY(player1_id) REFERENCES users(id),
+    FOREIGN KEY(player2_id) REFERENCES users(id),
+    FOREIGN KEY(match_id) REFERENCES matches(id),
+    FOREIGN KEY(winner_id) REFERENCES users(id),
+    UNIQUE(tournament_id, round, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tournament_matches_match ON tournament_matches(match_id);
@@ -0,0 +1,43 @@
+-- Double elimination: slots belong to a bracket and record where their players come from,
+-- so the whole bracket can be rebuilt from the linked match results
+ALTER TABLE tournaments ADD COLUMN grand_final_reset BOOLEAN NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS tournament_matches_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tournament_id INTEGER NOT NULL,
+    bracket TEXT NOT NULL DEFAULT 'winners', -- 'winners', 'losers', 'grand_final', 'grand_final_reset'
+    round INTEGER NOT NULL, -- 1 is the first round of its bracket
+    position INTEGER NOT NULL, -- 0-based, top to bottom
+    player1_source TEXT NOT NULL DEFAULT '{}', -- JSON: {"seed":n}, {"winner":slot} or {"loser":slot}
+    player2_source TEXT NOT NULL DEFAULT '{}',
+    player1_id INTEGER,
+    player2_id INTEGER,
+    match_id INTEGER,
+    winner_id INTEGER,
+    FOREIGN KEY(tournament_id) REFERENCES tournaments(id),
+    FOREIGN KEY(player1_id) REFERENCES users(id),
+    FOREIGN KEY(player2_id) REFERENCES users(id),
+    FOREIGN KEY(match_id) REFERENCES matches(id),
+    FOREIGN KEY(winner_id) REFERENCES users(id),
+    UNIQUE(tournament_id, bracket, round, position)
+);
+
+-- Existing single-elimination slots: first-round players by seed (seed 0 is a bye), later rounds
+-- from the two slots feeding them
+INSERT INTO tournament_matches_new (id, tournament_id, bracket, round, position, player1_source, player2_source, player1_id, player2_id, match_id, winner_id)
+SELECT t.id, t.tournament_id, 'winners', t.round, t.position,
+    CASE WHEN t.round = 1
+        THEN json_object('seed', COALESCE((SELECT e.seed FROM tournament_entries e WHERE e.tournament_id = t.tournament_id AND e.user_id = t.player1_id), 0))
+        ELSE json_object('winner', json_object('bracket', 'winners', 'round', t.round - 1, 'position', t.position * 2))
+    END,
+    CASE WHEN t.round = 1
+        THEN json_object('seed', COALESCE((SELECT e.seed FROM tournament_entries e WHERE e.tournament_id = t.tournament_id AND e.user_id = t.player2_id), 0))
+        ELSE json_object('winner', json_object('bracket', 'winners', 'round', t.round - 1, 'position', t.position * 2 + 1))
+    END,
+    t.player1_id, t.player2_id, t.match_id, t.winner_id
+FROM tournament_matches t;
+
+DROP TABLE tournament_matches;
+ALTER TABLE tournament_matches_new RENAME TO tournament_matches;
+
+CREATE INDEX IF NOT EXISTS idx_tournament_matches_match ON tournament_matches(match_id);
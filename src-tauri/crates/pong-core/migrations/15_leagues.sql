@@ -0,0 +1,38 @@
+-- Monthly leagues: everyone plays everyone in one game mode
+CREATE TABLE IF NOT EXISTS leagues (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    game_mode_id INTEGER NOT NULL,
+    double_round BOOLEAN NOT NULL DEFAULT 0, -- Every pairing is played twice, sides swapped
+    tie_breakers TEXT NOT NULL DEFAULT '["head_to_head","point_differential"]', -- JSON, applied in order
+    status TEXT NOT NULL DEFAULT 'registration', -- 'registration', 'in_progress', 'finished'
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(game_mode_id) REFERENCES game_modes(id)
+);
+
+CREATE TABLE IF NOT EXISTS league_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    league_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    FOREIGN KEY(league_id) REFERENCES leagues(id),
+    FOREIGN KEY(user_id) REFERENCES users(id),
+    UNIQUE(league_id, user_id)
+);
+
+-- Drawn when the league starts; results come from the linked match
+CREATE TABLE IF NOT EXISTS league_fixtures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    league_id INTEGER NOT NULL,
+    round INTEGER NOT NULL, -- 1 is the first round
+    position INTEGER NOT NULL, -- 0-based within the round
+    player1_id INTEGER NOT NULL,
+    player2_id INTEGER NOT NULL,
+    match_id INTEGER,
+    FOREIGN KEY(league_id) REFERENCES leagues(id),
+    FOREIGN KEY(player1_id) REFERENCES users(id),
+    FOREIGN KEY(player2_id) REFERENCES users(id),
+    FOREIGN KEY(match_id) REFERENCES matches(id),
+    UNIQUE(league_id, round, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_league_fixtures_match ON league_fixtures(match_id);
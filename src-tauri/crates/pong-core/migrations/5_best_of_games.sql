@@ -0,0 +1,2 @@
+ALTER TABLE game_modes ADD COLUMN games_to_win INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE matches ADD COLUMN games TEXT DEFAULT '[]'; -- JSON String of finished games
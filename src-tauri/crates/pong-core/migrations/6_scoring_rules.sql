@@ -0,0 +1,2 @@
+ALTER TABLE game_modes ADD COLUMN scoring_type TEXT NOT NULL DEFAULT 'classic'; -- 'classic', 'capped', 'golden_point', 'first_to'
+ALTER TABLE game_modes ADD COLUMN points_cap INTEGER; -- Only used by 'capped'
@@ -0,0 +1,17 @@
+ALTER TABLE game_modes ADD COLUMN archived BOOLEAN NOT NULL DEFAULT 0;
+
+-- Freeze the game mode rules into every existing match (version 1 snapshot)
+UPDATE matches SET match_rules = json_set(
+    CASE WHEN json_valid(match_rules) THEN match_rules ELSE '{}' END,
+    '$.version', 1,
+    '$.pointsToWin', COALESCE((SELECT points_to_win FROM game_modes WHERE id = matches.game_mode_id), 11),
+    '$.servesBeforeChange', COALESCE((SELECT serves_before_change FROM game_modes WHERE id = matches.game_mode_id), 2),
+    '$.isDeuceEnabled', json(CASE WHEN (SELECT is_deuce_enabled FROM game_modes WHERE id = matches.game_mode_id) THEN 'true' ELSE 'false' END),
+    '$.servesInDeuce', COALESCE(json_extract(match_rules, '$.servesInDeuce'), (SELECT serves_in_deuce FROM game_modes WHERE id = matches.game_mode_id), 1),
+    '$.serveType', COALESCE(json_extract(match_rules, '$.serveType'), (SELECT serve_type FROM game_modes WHERE id = matches.game_mode_id), 'free'),
+    '$.gamesToWin', COALESCE((SELECT games_to_win FROM game_modes WHERE id = matches.game_mode_id), 1),
+    '$.scoringType', COALESCE(json_extract(match_rules, '$.scoringType'), (SELECT scoring_type FROM game_modes WHERE id = matches.game_mode_id), 'classic'),
+    '$.pointsCap', COALESCE(json_extract(match_rules, '$.pointsCap'), (SELECT points_cap FROM game_modes WHERE id = matches.game_mode_id)),
+    '$.firstServerId', json_extract(match_rules, '$.firstServerId')
+)
+WHERE NOT json_valid(match_rules) OR json_extract(match_rules, '$.version') IS NULL;
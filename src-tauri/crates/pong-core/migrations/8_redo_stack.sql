@@ -0,0 +1,4 @@
+ALTER TABLE matches ADD COLUMN redo_events TEXT DEFAULT '[]'; -- JSON String of undone events (last undone at the end)
+
+INSERT OR IGNORE INTO key_bindings (action, key_code, label, is_default) VALUES
+    ('redo',            'KeyY',   'Y', 1);
@@ -0,0 +1 @@
+ALTER TABLE matches ADD COLUMN pauses TEXT DEFAULT '[]'; -- JSON String of pause intervals
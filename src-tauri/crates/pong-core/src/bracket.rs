@@ -0,0 +1,270 @@
+// Elimination brackets as data: every slot says where its two players come from (a seed, or
+// the winner or loser of an earlier slot), and `resolve` derives the whole bracket from the
+// match results. The repository stores the slots; nothing here touches the database.
+
+use crate::models::User;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+pub const SINGLE_ELIMINATION: &str = "single_elimination";
+pub const DOUBLE_ELIMINATION: &str = "double_elimination";
+pub const FORMATS: [&str; 2] = [SINGLE_ELIMINATION, DOUBLE_ELIMINATION];
+
+pub const WINNERS: &str = "winners";
+pub const LOSERS: &str = "losers";
+pub const GRAND_FINAL: &str = "grand_final";
+// Only played when the losers bracket champion wins the grand final
+pub const GRAND_FINAL_RESET: &str = "grand_final_reset";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct SlotKey {
+    pub bracket: String,
+    pub round: i64,
+    pub position: i64,
+}
+
+impl SlotKey {
+    pub fn new(bracket: &str, round: i64, position: i64) -> Self {
+        SlotKey { bracket: bracket.to_string(), round, position }
+    }
+}
+
+// Stored as JSON: {"seed":3}, {"winner":{"bracket":"winners","round":1,"position":0}}, ...
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Seed(i64), // 1-based; a seed nobody holds is a bye
+    Winner(SlotKey),
+    Loser(SlotKey),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotPlan {
+    pub key: SlotKey,
+    pub player1: Source,
+    pub player2: Source,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Pending,
+    // A played match, a bye (no loser) or a slot nobody reaches (neither)
+    Decided { winner: Option<i64>, loser: Option<i64> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotState {
+    pub player1: Option<i64>,
+    pub player2: Option<i64>,
+    pub outcome: Outcome,
+}
+
+impl SlotState {
+    pub fn winner(&self) -> Option<i64> {
+        match self.outcome {
+            Outcome::Decided { winner, .. } => winner,
+            Outcome::Pending => None,
+        }
+    }
+}
+
+fn win_rate(user: &User) -> f64 {
+    if user.matches_played > 0 {
+        user.wins as f64 / user.matches_played as f64
+    } else {
+        0.0
+    }
+}
+
+// Best first: win rate, then wins; players still level keep their registration order
+pub fn seed_order(players: &[User]) -> Vec<i64> {
+    let mut ordered: Vec<&User> = players.iter().collect();
+    ordered.sort_by(|a, b| {
+        win_rate(b)
+            .partial_cmp(&win_rate(a))
+            .unwrap_or(Ordering::Equal)
+            .then(b.wins.cmp(&a.wins))
+    });
+    ordered.iter().map(|u| u.id).collect()
+}
+
+// 1-based seeds in bracket order, so the top two seeds can only meet in the final:
+// 8 slots give [1, 8, 4, 5, 2, 7, 3, 6]. Missing seeds (byes) land opposite the top seeds.
+pub fn seed_positions(size: usize) -> Vec<usize> {
+    let mut positions = vec![1];
+    while positions.len() < size {
+        let mirror = positions.len() * 2 + 1;
+        positions = positions.iter().flat_map(|&s| [s, mirror - s]).collect();
+    }
+    positions
+}
+
+// Slots in play order: every slot comes after the slots its players come from
+pub fn plan(format: &str, players: usize, grand_final_reset: bool) -> Vec<SlotPlan> {
+    let size = players.next_power_of_two();
+    let rounds = size.trailing_zeros() as i64;
+    let winner = |bracket: &str, round: i64, position: i64| Source::Winner(SlotKey::new(bracket, round, position));
+    let loser = |bracket: &str, round: i64, position: i64| Source::Loser(SlotKey::new(bracket, round, position));
+    let mut slots = Vec::new();
+
+    let seeds = seed_positions(size);
+    for (position, pair) in seeds.chunks(2).enumerate() {
+        slots.push(SlotPlan {
+            key: SlotKey::new(WINNERS, 1, position as i64),
+            player1: Source::Seed(pair[0] as i64),
+            player2: Source::Seed(pair[1] as i64),
+        });
+    }
+    for round in 2..=rounds {
+        for position in 0..(size as i64 >> round) {
+            slots.push(SlotPlan {
+                key: SlotKey::new(WINNERS, round, position),
+                player1: winner(WINNERS, round - 1, position * 2),
+                player2: winner(WINNERS, round - 1, position * 2 + 1),
+            });
+        }
+    }
+    if format != DOUBLE_ELIMINATION {
+        return slots;
+    }
+
+    // Losers bracket: first-round losers play each other, then every other round takes in
+    // the losers of the next winners round, in reverse order to avoid early rematches
+    let mut last_losers_round = 0;
+    if rounds >= 2 {
+        for position in 0..(size as i64 / 4) {
+            slots.push(SlotPlan {
+                key: SlotKey::new(LOSERS, 1, position),
+                player1: loser(WINNERS, 1, position * 2),
+                player2: loser(WINNERS, 1, position * 2 + 1),
+            });
+        }
+        for j in 1..rounds {
+            let count = size as i64 >> (j + 1);
+            for position in 0..count {
+                slots.push(SlotPlan {
+                    key: SlotKey::new(LOSERS, 2 * j, position),
+                    player1: winner(LOSERS, 2 * j - 1, position),
+                    player2: loser(WINNERS, j + 1, count - 1 - position),
+                });
+            }
+            if j < rounds - 1 {
+                for position in 0..count / 2 {
+                    slots.push(SlotPlan {
+                        key: SlotKey::new(LOSERS, 2 * j + 1, position),
+                        player1: winner(LOSERS, 2 * j, position * 2),
+                        player2: winner(LOSERS, 2 * j, position * 2 + 1),
+                    });
+                }
+            }
+        }
+        last_losers_round = 2 * (rounds - 1);
+    }
+
+    let losers_champion = match last_losers_round {
+        0 => loser(WINNERS, 1, 0),
+        round => winner(LOSERS, round, 0),
+    };
+    slots.push(SlotPlan {
+        key: SlotKey::new(GRAND_FINAL, 1, 0),
+        player1: winner(WINNERS, rounds, 0),
+        player2: losers_champion,
+    });
+    if grand_final_reset {
+        slots.push(SlotPlan {
+            key: SlotKey::new(GRAND_FINAL_RESET, 1, 0),
+            player1: loser(GRAND_FINAL, 1, 0),
+            player2: winner(GRAND_FINAL, 1, 0),
+        });
+    }
+    slots
+}
+
+// Derives every slot from the seeds and the results. `winner_of(index, p1, p2)` returns the
+// winner of the match played in that slot, if it was between exactly these two players.
+pub fn resolve(plan: &[SlotPlan], seeded: &[i64], winner_of: impl Fn(usize, i64, i64) -> Option<i64>) -> Vec<SlotState> {
+    let index: HashMap<&SlotKey, usize> = plan.iter().enumerate().map(|(i, s)| (&s.key, i)).collect();
+    let mut states: Vec<SlotState> = Vec::with_capacity(plan.len());
+
+    for (i, slot) in plan.iter().enumerate() {
+        // None while the source is still being played, Some(None) for nobody
+        let known = |source: &Source| -> Option<Option<i64>> {
+            match source {
+                Source::Seed(seed) => Some(usize::try_from(*seed - 1).ok().and_then(|s| seeded.get(s).copied())),
+                Source::Winner(key) | Source::Loser(key) => {
+                    match states.get(*index.get(key)?)?.outcome {
+                        Outcome::Pending => None,
+                        Outcome::Decided { winner, loser } => Some(if matches!(source, Source::Winner(_)) { winner } else { loser }),
+                    }
+                }
+            }
+        };
+        let (known1, known2) = (known(&slot.player1), known(&slot.player2));
+        let player1 = known1.flatten();
+        let player2 = known2.flatten();
+
+        // The reset is not needed when the winners bracket champion took the grand final
+        let reset_skipped = slot.key.bracket == GRAND_FINAL_RESET
+            && index
+                .get(&SlotKey::new(GRAND_FINAL, 1, 0))
+                .and_then(|&gf| states.get(gf))
+                .is_some_and(|gf| gf.winner().is_some() && gf.winner() == gf.player1);
+
+        let outcome = match (known1, known2) {
+            _ if reset_skipped => Outcome::Decided { winner: None, loser: None },
+            (Some(Some(a)), Some(Some(b))) => match winner_of(i, a, b) {
+                Some(w) => Outcome::Decided { winner: Some(w), loser: Some(if w == a { b } else { a }) },
+                None => Outcome::Pending,
+            },
+            (Some(only), Some(None)) | (Some(None), Some(only)) => Outcome::Decided { winner: only, loser: None },
+            _ => Outcome::Pending,
+        };
+        states.push(SlotState { player1, player2, outcome });
+    }
+    states
+}
+
+// The tournament winner, once every slot is decided
+pub fn champion(states: &[SlotState]) -> Option<i64> {
+    if states.iter().any(|s| s.outcome == Outcome::Pending) {
+        return None;
+    }
+    states.iter().rev().find_map(SlotState::winner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byes_go_to_the_top_seeds() {
+        assert_eq!(seed_positions(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+        let slots = plan(SINGLE_ELIMINATION, 5, false);
+        assert_eq!(slots.len(), 7);
+
+        // Only seeds 4 and 5 play in the first round
+        let states = resolve(&slots, &[10, 20, 30, 40, 50], |_, _, _| None);
+        let byes: Vec<Option<i64>> = states[..4].iter().map(SlotState::winner).collect();
+        assert_eq!(byes, vec![Some(10), None, Some(20), Some(30)]);
+        assert_eq!((states[1].player1, states[1].player2), (Some(40), Some(50)));
+    }
+
+    #[test]
+    fn grand_final_reset_is_only_played_after_a_losers_bracket_win() {
+        // Four players: 3 winners + 2 losers bracket slots, grand final and reset
+        let slots = plan(DOUBLE_ELIMINATION, 4, true);
+        assert_eq!(slots.len(), 7);
+        let seeded = [1, 2, 3, 4];
+
+        // The better seed wins everything: no reset
+        let favourites = resolve(&slots, &seeded, |_, a, b| Some(a.min(b)));
+        assert_eq!(champion(&favourites), Some(1));
+        assert_eq!(favourites[6].outcome, Outcome::Decided { winner: None, loser: None });
+
+        // Seed 2 comes back through the losers bracket and wins the grand final and the reset
+        let comeback = resolve(&slots, &seeded, |i, a, b| if i >= 5 { Some(a.max(b).min(2)) } else { Some(a.min(b)) });
+        assert_eq!((comeback[5].player1, comeback[5].player2), (Some(1), Some(2)));
+        assert_eq!(champion(&comeback), Some(2));
+    }
+}
@@ -0,0 +1,41 @@
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Pool, Sqlite};
+use std::str::FromStr;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+// Opens (creating it if needed) and migrates the database at `db_url`
+pub async fn open(db_url: &str) -> Result<Pool<Sqlite>, String> {
+    let options = SqliteConnectOptions::from_str(db_url)
+        .map_err(|e| e.to_string())?
+        .create_if_missing(true);
+    connect(options).await
+}
+
+// Database that lives as long as the pool, shared by all of its connections.
+// `name` keeps separate in-memory databases apart, e.g. one per test.
+pub async fn open_in_memory(name: &str) -> Result<Pool<Sqlite>, String> {
+    let options = SqliteConnectOptions::new()
+        .filename(format!("/{}", name))
+        .vfs("memdb")
+        .create_if_missing(true);
+    connect(options).await
+}
+
+async fn connect(options: SqliteConnectOptions) -> Result<Pool<Sqlite>, String> {
+    // Enforce the REFERENCES clauses on every pooled connection; set here rather than left to
+    // the sqlx default, so every pool built by open or open_in_memory has it
+    let pool = SqlitePoolOptions::new()
+        .max_connections(5)
+        .connect_with(options.foreign_keys(true))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    MIGRATOR
+        .run(&pool)
+        .await
+        .map_err(|e| format!("Migration failed: {}", e))?;
+
+    Ok(pool)
+}
@@ -0,0 +1,125 @@
+// Error type returned by every command.
+// Serialized as { "code": "...", "message": "..." } so the frontend can branch on the code,
+// plus a "reason" for validation errors that have one (see the constants in rules.rs);
+// database and JSON failures are logged with their cause before being reported.
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+// Returned when a match changed between read and write; the frontend reloads the match
+pub const CONFLICT_MESSAGE: &str = "match was updated by another action, reload it and try again";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    NotFound(String),
+    // Input that can never be accepted (bad lineup, bad game mode, ...)
+    Validation { message: String, reason: Option<&'static str> },
+    Conflict(String),      // Another write got there first, retrying on fresh data may work
+    Database(String),
+    RuleViolation(String), // Valid input the match doesn't allow right now (finished, paused, ...)
+    Internal(String),      // The app itself failed (opening a window, ...), not the data
+}
+
+impl AppError {
+    pub fn conflict() -> Self {
+        AppError::Conflict(CONFLICT_MESSAGE.to_string())
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation { message: message.into(), reason: None }
+    }
+
+    // Validation error the frontend can tell apart without parsing the message
+    pub fn invalid(reason: &'static str, message: impl Into<String>) -> Self {
+        AppError::Validation { message: message.into(), reason: Some(reason) }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Validation { .. } => "VALIDATION",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::Database(_) => "DATABASE",
+            AppError::RuleViolation(_) => "RULE_VIOLATION",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NotFound(m)
+            | AppError::Validation { message: m, .. }
+            | AppError::Conflict(m)
+            | AppError::Database(m)
+            | AppError::RuleViolation(m)
+            | AppError::Internal(m) => m,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code(), self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 3)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", self.message())?;
+        match self {
+            AppError::Validation { reason: Some(reason), .. } => s.serialize_field("reason", reason)?,
+            _ => s.skip_field("reason")?,
+        }
+        s.end()
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        // SQLite reports a competing writer as BUSY (5) or LOCKED (6), possibly as an extended code
+        if let Some(db_err) = e.as_database_error() {
+            let code = db_err.code().and_then(|c| c.parse::<i64>().ok()).unwrap_or(0);
+            if matches!(code & 0xff, 5 | 6) {
+                log::warn!("Write conflict: {}", e);
+                return AppError::conflict();
+            }
+        }
+        if let sqlx::Error::RowNotFound = e {
+            return AppError::NotFound("Record not found".to_string());
+        }
+        log::error!("Database error: {}", e);
+        AppError::Database("Database error, see the log for details".to_string())
+    }
+}
+
+// Stored JSON columns that no longer parse
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        log::error!("Stored data could not be read: {}", e);
+        AppError::Database("Stored match data is corrupted".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_and_message() {
+        let json = serde_json::to_value(AppError::RuleViolation("Match is finished".to_string())).unwrap();
+        assert_eq!(json, serde_json::json!({ "code": "RULE_VIOLATION", "message": "Match is finished" }));
+
+        let json = serde_json::to_value(AppError::invalid("UNKNOWN_PLAYER", "User 99 does not exist")).unwrap();
+        assert_eq!(json, serde_json::json!({ "code": "VALIDATION", "message": "User 99 does not exist", "reason": "UNKNOWN_PLAYER" }));
+    }
+
+    #[test]
+    fn missing_row_is_not_found() {
+        assert!(matches!(AppError::from(sqlx::Error::RowNotFound), AppError::NotFound(_)));
+        assert_eq!(AppError::from(sqlx::Error::PoolTimedOut).code(), "DATABASE");
+    }
+}
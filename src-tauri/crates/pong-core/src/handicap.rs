@@ -0,0 +1,55 @@
+// Handicap suggestions.
+// A player's strength is the share of points they won in past matches; the weaker
+// player starts each game with the gap between the two shares, scaled to the game length.
+
+use crate::models::ScoreSnapshot;
+
+// Players without finished matches are assumed to be even
+const DEFAULT_POINTS_SHARE: f64 = 0.5;
+
+pub fn points_share(points_scored: i64, points_conceded: i64) -> f64 {
+    let total = points_scored + points_conceded;
+    if total > 0 {
+        points_scored as f64 / total as f64
+    } else {
+        DEFAULT_POINTS_SHARE
+    }
+}
+
+pub fn suggest_handicap(p1_share: f64, p2_share: f64, points_to_win: i64) -> ScoreSnapshot {
+    // Never more than half a game, or the stronger player can barely play
+    let gap = ((p1_share - p2_share).abs() * points_to_win as f64).round() as i64;
+    let gap = gap.min(points_to_win / 2);
+
+    if p1_share > p2_share {
+        ScoreSnapshot { p1: 0, p2: gap }
+    } else {
+        ScoreSnapshot { p1: gap, p2: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weaker_player_gets_the_gap() {
+        let start = suggest_handicap(points_share(60, 40), points_share(40, 60), 11);
+        assert_eq!((start.p1, start.p2), (0, 2));
+
+        let start = suggest_handicap(0.3, 0.7, 21);
+        assert_eq!((start.p1, start.p2), (8, 0));
+    }
+
+    #[test]
+    fn even_or_unknown_players_start_level() {
+        let start = suggest_handicap(points_share(0, 0), points_share(50, 50), 11);
+        assert_eq!((start.p1, start.p2), (0, 0));
+    }
+
+    #[test]
+    fn handicap_is_capped_at_half_a_game() {
+        let start = suggest_handicap(1.0, 0.0, 11);
+        assert_eq!((start.p1, start.p2), (0, 5));
+    }
+}
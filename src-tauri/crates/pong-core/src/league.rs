@@ -0,0 +1,191 @@
+// Round-robin leagues: fixtures drawn with the circle method, and a standings table ranked by
+// wins and then by the league's tie-breakers. The repository feeds it the finished matches.
+
+use crate::models::LeagueStanding;
+use std::collections::HashMap;
+
+pub const HEAD_TO_HEAD: &str = "head_to_head";
+pub const POINT_DIFFERENTIAL: &str = "point_differential";
+pub const TIE_BREAKERS: [&str; 2] = [HEAD_TO_HEAD, POINT_DIFFERENTIAL];
+
+// Always the first criterion, before the configurable tie-breakers
+const WINS: &str = "wins";
+
+// Players are indexes into the list passed to `fixtures`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pairing {
+    pub round: i64,
+    pub position: i64,
+    pub player1: usize,
+    pub player2: usize,
+}
+
+// A finished league match; points are the ones actually won, see stats::points_won
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LeagueResult {
+    pub player1: i64,
+    pub player2: i64,
+    pub winner: i64,
+    pub points1: i64,
+    pub points2: i64,
+}
+
+// Circle method: the first player stays put while the others rotate one place per round, so
+// everyone meets once in n - 1 rounds. With an odd count a bye joins the ring and whoever
+// faces it sits the round out. The double round replays every round with sides swapped.
+pub fn fixtures(players: usize, double_round: bool) -> Vec<Pairing> {
+    let mut ring: Vec<Option<usize>> = (0..players).map(Some).collect();
+    if players % 2 == 1 {
+        ring.push(None);
+    }
+    let n = ring.len();
+    let rounds = n.saturating_sub(1);
+    let mut pairings = Vec::new();
+    for round in 0..rounds {
+        let mut position = 0;
+        for i in 0..n / 2 {
+            // Swap sides of the fixed player every other round so they are not always player 1
+            let (a, b) = if i == 0 && round % 2 == 1 { (ring[n - 1], ring[0]) } else { (ring[i], ring[n - 1 - i]) };
+            if let (Some(player1), Some(player2)) = (a, b) {
+                pairings.push(Pairing { round: round as i64 + 1, position, player1, player2 });
+                position += 1;
+            }
+        }
+        ring[1..].rotate_right(1);
+    }
+    if double_round {
+        let second_leg: Vec<Pairing> = pairings
+            .iter()
+            .map(|p| Pairing { round: p.round + rounds as i64, position: p.position, player1: p.player2, player2: p.player1 })
+            .collect();
+        pairings.extend(second_leg);
+    }
+    pairings
+}
+
+// Value of `criterion` for `row` within a group of tied players; higher ranks first
+fn criterion_value(criterion: &str, row: &LeagueStanding, group: &[i64], results: &[LeagueResult]) -> i64 {
+    match criterion {
+        WINS => row.won,
+        // Wins in the matches between the tied players only
+        HEAD_TO_HEAD => results
+            .iter()
+            .filter(|r| r.winner == row.user_id && group.contains(&r.player1) && group.contains(&r.player2))
+            .count() as i64,
+        POINT_DIFFERENTIAL => row.point_differential,
+        _ => 0,
+    }
+}
+
+// Orders `group` by the first criterion, then splits every run still level on to the next one
+fn rank(group: Vec<usize>, criteria: &[&str], rows: &[LeagueStanding], results: &[LeagueResult]) -> Vec<usize> {
+    let Some((criterion, rest)) = criteria.split_first() else {
+        return group;
+    };
+    if group.len() < 2 {
+        return group;
+    }
+    let ids: Vec<i64> = group.iter().map(|&i| rows[i].user_id).collect();
+    let mut keyed: Vec<(i64, usize)> = group.iter().map(|&i| (criterion_value(criterion, &rows[i], &ids, results), i)).collect();
+    keyed.sort_by_key(|&(value, _)| std::cmp::Reverse(value));
+    keyed
+        .chunk_by(|a, b| a.0 == b.0)
+        .flat_map(|run| rank(run.iter().map(|&(_, i)| i).collect(), rest, rows, results))
+        .collect()
+}
+
+// `players` in registration order; players still level after every tie-breaker keep that order
+pub fn standings(players: &[i64], results: &[LeagueResult], tie_breakers: &[String]) -> Vec<LeagueStanding> {
+    let mut rows: Vec<LeagueStanding> = players
+        .iter()
+        .map(|&user_id| LeagueStanding {
+            rank: 0,
+            user_id,
+            played: 0,
+            won: 0,
+            lost: 0,
+            points_for: 0,
+            points_against: 0,
+            point_differential: 0,
+        })
+        .collect();
+    let index: HashMap<i64, usize> = players.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+    for r in results {
+        for (player, scored, conceded) in [(r.player1, r.points1, r.points2), (r.player2, r.points2, r.points1)] {
+            let Some(&i) = index.get(&player) else { continue };
+            let row = &mut rows[i];
+            row.played += 1;
+            if r.winner == player {
+                row.won += 1;
+            } else {
+                row.lost += 1;
+            }
+            row.points_for += scored;
+            row.points_against += conceded;
+            row.point_differential = row.points_for - row.points_against;
+        }
+    }
+
+    let criteria: Vec<&str> = std::iter::once(WINS).chain(tie_breakers.iter().map(String::as_str)).collect();
+    let order = rank((0..rows.len()).collect(), &criteria, &rows, results);
+    order
+        .into_iter()
+        .enumerate()
+        .map(|(position, i)| LeagueStanding { rank: position as i64 + 1, ..rows[i].clone() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn everyone_meets_once_per_round_robin() {
+        // Five players: five rounds of two matches, one player resting each round
+        let single = fixtures(5, false);
+        assert_eq!(single.len(), 10);
+        assert_eq!(single.iter().map(|p| p.round).max(), Some(5));
+        let mut pairs: Vec<(usize, usize)> = single.iter().map(|p| (p.player1.min(p.player2), p.player1.max(p.player2))).collect();
+        pairs.sort();
+        pairs.dedup();
+        assert_eq!(pairs.len(), 10);
+        for round in 1..=5 {
+            let playing: Vec<usize> = single.iter().filter(|p| p.round == round).flat_map(|p| [p.player1, p.player2]).collect();
+            assert_eq!(playing.len(), 4);
+        }
+
+        // The second leg swaps sides
+        let double = fixtures(4, true);
+        assert_eq!(double.len(), 12);
+        assert_eq!((double[6].round, double[6].player1, double[6].player2), (4, double[0].player2, double[0].player1));
+    }
+
+    #[test]
+    fn tie_breakers_apply_in_the_configured_order() {
+        let result = |player1, player2, winner, points1, points2| LeagueResult { player1, player2, winner, points1, points2 };
+        // 1 and 2 have two wins each: 1 beat 2, 2 has the better differential.
+        // 3 and 4 have one each: 4 beat 3, 3 has the better differential.
+        let results = [
+            result(1, 2, 1, 11, 9),
+            result(1, 3, 3, 0, 11),
+            result(1, 4, 1, 11, 9),
+            result(2, 3, 2, 11, 0),
+            result(2, 4, 2, 11, 0),
+            result(3, 4, 4, 9, 11),
+        ];
+        let order = |tie_breakers: &[&str]| -> Vec<i64> {
+            let tie_breakers: Vec<String> = tie_breakers.iter().map(|t| t.to_string()).collect();
+            standings(&[1, 2, 3, 4], &results, &tie_breakers).iter().map(|s| s.user_id).collect()
+        };
+
+        assert_eq!(order(&[HEAD_TO_HEAD, POINT_DIFFERENTIAL]), vec![1, 2, 4, 3]);
+        assert_eq!(order(&[POINT_DIFFERENTIAL, HEAD_TO_HEAD]), vec![2, 1, 3, 4]);
+        assert_eq!(order(&[]), vec![1, 2, 3, 4]);
+
+        let table = standings(&[1, 2, 3, 4], &results, &[HEAD_TO_HEAD.to_string()]);
+        let first = &table[0];
+        assert_eq!((first.rank, first.played, first.won, first.lost), (1, 3, 2, 1));
+        assert_eq!((first.points_for, first.points_against, first.point_differential), (22, 29, -7));
+    }
+}
@@ -0,0 +1,19 @@
+// Pong Ping core: match rules, scoring and statistics, plus the SQLite storage behind them.
+// Everything here runs without Tauri; the app's commands are thin wrappers over `Repository`.
+
+pub mod bracket;
+pub mod db;
+pub mod error;
+pub mod handicap;
+pub mod league;
+pub mod live;
+pub mod models;
+pub mod replay;
+pub mod repository;
+pub mod rules;
+pub mod scoring;
+pub mod serve;
+pub mod stats;
+
+pub use error::AppError;
+pub use repository::Repository;
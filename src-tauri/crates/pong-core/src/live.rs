@@ -0,0 +1,30 @@
+// Live feed of match changes, for spectator scoreboards and the app windows.
+// Writers publish after their transaction commits; publishing with nobody listening is a no-op.
+
+use crate::models::PopulatedMatch;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+// A subscriber that falls this far behind skips ahead instead of slowing down writers
+const CAPACITY: usize = 64;
+
+#[derive(Clone)]
+pub struct LiveFeed {
+    sender: broadcast::Sender<Arc<PopulatedMatch>>,
+}
+
+impl Default for LiveFeed {
+    fn default() -> Self {
+        LiveFeed { sender: broadcast::channel(CAPACITY).0 }
+    }
+}
+
+impl LiveFeed {
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<PopulatedMatch>> {
+        self.sender.subscribe()
+    }
+
+    pub fn publish(&self, m: &PopulatedMatch) {
+        let _ = self.sender.send(Arc::new(m.clone()));
+    }
+}
@@ -0,0 +1,482 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+// --- User ---
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub name: String,
+    pub fun_nickname: Option<String>,
+    pub avatar: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub wins: i64,
+    pub matches_played: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserDto {
+    pub name: String,
+    pub fun_nickname: Option<String>,
+    pub avatar: Option<String>,
+    pub color: String,
+    pub icon: String,
+}
+
+// --- GameMode ---
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameMode {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub name: String,
+    pub points_to_win: i64,
+    pub serves_before_change: i64,
+    pub rules_description: Option<String>,
+    pub is_deuce_enabled: bool,
+    pub serves_in_deuce: i64,
+    pub serve_type: Option<String>,
+    pub games_to_win: i64, // 1 = single game, 2 = best of 3, 3 = best of 5
+    pub scoring_type: String, // 'classic', 'capped', 'golden_point', 'first_to'
+    pub points_cap: Option<i64>,
+    pub archived: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGameModeDto {
+    pub name: String,
+    pub points_to_win: i64,
+    pub serves_before_change: i64,
+    pub rules_description: Option<String>,
+    pub is_deuce_enabled: bool,
+    pub serves_in_deuce: i64,
+    pub serve_type: String,
+    pub games_to_win: i64,
+    pub scoring_type: String,
+    pub points_cap: Option<i64>,
+}
+
+// --- Match ---
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Match {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub player1_id: i64,
+    pub player2_id: i64,
+    pub player3_id: Option<i64>, // Doubles partner for P1
+    pub player4_id: Option<i64>, // Doubles partner for P2
+    pub game_mode_id: i64,
+    pub status: String, // 'in_progress', 'finished', 'abandoned'
+    pub score_p1: i64,
+    pub score_p2: i64,
+    pub events: String, // JSON String
+    pub start_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub winner_id: Option<i64>,
+    pub match_rules: String, // JSON String
+    pub games: String, // JSON String of finished games
+    pub redo_events: String, // JSON String of undone events
+    pub pauses: String, // JSON String of pause intervals
+    pub version: i64, // Bumped on every write
+}
+
+// Lineup and per-match overrides of the game mode for start_match
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StartMatchDto {
+    pub player1_id: i64,
+    pub player2_id: i64,
+    pub player3_id: Option<i64>,
+    pub player4_id: Option<i64>,
+    pub game_mode_id: i64,
+    pub serves_in_deuce: Option<i64>,
+    pub serve_type: Option<String>,
+    pub handicap_p1: Option<i64>,
+    pub handicap_p2: Option<i64>,
+}
+
+// Structs for JSON fields parsing
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchEventType {
+    Point,         // Rally won, no specific reason
+    Ace,           // Unreturned serve
+    ServiceFault,  // Point to the receiver
+    UnforcedError, // Point to the opponent of whoever missed
+    EdgeBall,
+    Net,
+    Let,           // Replayed, no score change
+    Penalty,       // Penalty point awarded by the umpire
+    SideSwitch,    // Generated by the reducer
+    Timeout,       // element_id is the player who called it
+    Forfeit,       // element_id is a player of the conceding side, ends the match
+    Handicap,      // Starting points for element_id's side, generated by the reducer
+}
+
+impl MatchEventType {
+    pub fn is_point(&self) -> bool {
+        !matches!(
+            self,
+            MatchEventType::Let
+                | MatchEventType::SideSwitch
+                | MatchEventType::Timeout
+                | MatchEventType::Forfeit
+                | MatchEventType::Handicap
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ForfeitReason {
+    Walkover,         // Did not show up
+    Retirement,       // Gave up during the match (injury, time...)
+    Disqualification,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchEvent {
+    #[serde(rename = "type")]
+    pub event_type: MatchEventType,
+    pub element_id: Option<String>, // Player credited with the point
+    pub timestamp: i64,
+    pub score_snapshot: ScoreSnapshot,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forfeit_reason: Option<ForfeitReason>, // Only on forfeit events
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreSnapshot {
+    pub p1: i64,
+    pub p2: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseInterval {
+    pub started_at: i64, // Unix millis
+    pub ended_at: Option<i64>, // None while the match is paused
+}
+
+// Which team ('p1' or 'p2') currently plays on each physical end
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CourtSides {
+    pub left: String,
+    pub right: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameScore {
+    pub game_number: i64,
+    pub p1: i64,
+    pub p2: i64,
+    pub winner: String, // 'p1' or 'p2'
+}
+
+pub const MATCH_RULES_VERSION: i64 = 2;
+
+// Snapshot of the game mode taken at start_match; editing the mode never changes it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchRules {
+    pub version: i64,
+    pub points_to_win: i64,
+    pub serves_before_change: i64,
+    pub is_deuce_enabled: bool,
+    pub serves_in_deuce: i64,
+    pub serve_type: String,
+    pub games_to_win: i64,
+    pub scoring_type: String,
+    pub points_cap: Option<i64>,
+    pub first_server_id: Option<i64>,
+    #[serde(default)]
+    pub handicap_p1: i64, // Starting score of each game, added in version 2
+    #[serde(default)]
+    pub handicap_p2: i64,
+}
+
+// Result of a match as recorded in the audit log
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchResultSnapshot {
+    pub games: Vec<GameScore>,
+    pub winner_id: Option<i64>,
+    pub events: Vec<MatchEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchAuditEntry {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub match_id: i64,
+    pub action: String, // 'edit_result'
+    pub before: String, // JSON String of MatchResultSnapshot
+    pub after: String, // JSON String of MatchResultSnapshot
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Populated Response structs (to mimic Mongoose populate)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PopulatedMatch {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub player1: User,
+    pub player2: User,
+    pub player3: Option<User>,
+    pub player4: Option<User>,
+    pub game_mode: GameMode,
+    pub status: String,
+    pub version: i64,
+    pub score: ScoreSnapshot, // Current game
+    pub games: Vec<GameScore>,
+    pub games_won: ScoreSnapshot,
+    pub events: Vec<MatchEvent>,
+    pub redo_count: i64,
+    pub court_sides: CourtSides,
+    pub timeouts_used: ScoreSnapshot,
+    pub is_paused: bool,
+    pub active_duration_ms: i64, // Playing time, pauses excluded
+    pub match_rules: MatchRules,
+    pub winner: Option<User>,
+    pub first_server: Option<i64>,
+    pub current_server_id: Option<i64>,
+    pub next_server_id: Option<i64>,
+    pub serves_remaining: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStatistics {
+    pub user_id: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub matches_played: i64,
+    pub win_rate: f64,
+    pub current_streak: i64,
+    pub best_streak: i64,
+    pub points_scored: i64,
+    pub points_conceded: i64,
+    pub aces: i64,
+    pub aces_per_match: f64,
+    pub errors: i64, // Service faults + unforced errors committed
+    pub error_rate: f64, // Errors per point played
+    pub average_match_duration_ms: i64, // Active playing time only
+    pub forfeit_wins: i64, // Included in wins
+    pub forfeit_losses: i64, // Included in losses
+    pub forfeits: Vec<ForfeitStat>,
+    pub points_won_by: Vec<PointReasonStat>,
+    pub mode_stats: Vec<ModeStat>,
+    pub recent_matches: Vec<RecentMatch>,
+    pub nemesis: Option<OpponentStat>,
+    pub victim: Option<OpponentStat>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModeStat {
+    pub mode_name: String,
+    pub wins: i64,
+    pub losses: i64,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointReasonStat {
+    pub reason: MatchEventType,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForfeitStat {
+    pub reason: ForfeitReason,
+    pub wins: i64, // Opponent conceded
+    pub losses: i64, // User conceded
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpponentStat {
+    pub opponent_id: i64,
+    pub opponent_name: String,
+    pub count: i64, 
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentMatch {
+    pub match_id: i64,
+    pub date: String,
+    pub opponent_name: String,
+    pub result: String, // 'Win' or 'Loss'
+    pub score_user: i64,
+    pub score_opponent: i64,
+    pub mode_name: String,
+    pub forfeit_reason: Option<ForfeitReason>, // Set when the match ended by forfeit
+}
+
+// Outcome of Repository::recompute_statistics
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputeSummary {
+    pub matches_rebuilt: i64,
+    pub users_updated: i64,
+    pub results_changed: i64, // Matches whose winner differed from the stored one
+}
+
+// --- Key Binding ---
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBinding {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub action: String,
+    pub key_code: String,
+    pub label: String,
+    pub is_default: bool,
+}
+
+
+// --- Tournament ---
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Tournament {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub name: String,
+    pub format: String, // 'single_elimination' or 'double_elimination'
+    pub game_mode_id: i64,
+    pub status: String, // 'registration', 'in_progress', 'finished'
+    pub winner_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub grand_final_reset: bool, // Double elimination only
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTournamentDto {
+    pub name: String,
+    pub game_mode_id: i64,
+    pub format: String,
+    pub grand_final_reset: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TournamentEntry {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub tournament_id: i64,
+    pub user_id: i64,
+    pub seed: Option<i64>,
+}
+
+// A slot of the bracket; player ids stay empty until the slots feeding it are decided.
+// Players and winner are derived from the sources and the linked match, see bracket::resolve.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BracketMatch {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub tournament_id: i64,
+    pub bracket: String, // 'winners', 'losers', 'grand_final', 'grand_final_reset'
+    pub round: i64,
+    pub position: i64,
+    pub player1_source: String, // JSON String of bracket::Source
+    pub player2_source: String,
+    pub player1_id: Option<i64>,
+    pub player2_id: Option<i64>,
+    pub match_id: Option<i64>,
+    pub winner_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TournamentBracket {
+    pub tournament: Tournament,
+    pub entries: Vec<TournamentEntry>, // Seed order once drawn, registration order before
+    pub matches: Vec<BracketMatch>, // In play order
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct League {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub name: String,
+    pub game_mode_id: i64,
+    pub double_round: bool,
+    pub tie_breakers: String, // JSON String, e.g. ["head_to_head","point_differential"]
+    pub status: String, // 'registration', 'in_progress', 'finished'
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLeagueDto {
+    pub name: String,
+    pub game_mode_id: i64,
+    pub double_round: bool,
+    pub tie_breakers: Vec<String>, // Applied in order, see league::TIE_BREAKERS
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueEntry {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub league_id: i64,
+    pub user_id: i64,
+}
+
+// Status and winner come from the linked match; an abandoned match leaves the fixture pending
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueFixture {
+    #[serde(rename = "_id")]
+    pub id: i64,
+    pub league_id: i64,
+    pub round: i64,
+    pub position: i64,
+    pub player1_id: i64,
+    pub player2_id: i64,
+    pub match_id: Option<i64>,
+    pub status: String, // 'pending', 'in_progress', 'finished'
+    pub winner_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueStanding {
+    pub rank: i64,
+    pub user_id: i64,
+    pub played: i64,
+    pub won: i64,
+    pub lost: i64,
+    pub points_for: i64,
+    pub points_against: i64,
+    pub point_differential: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeagueTable {
+    pub league: League,
+    pub entries: Vec<LeagueEntry>, // Registration order
+    pub fixtures: Vec<LeagueFixture>, // By round
+    pub standings: Vec<LeagueStanding>, // Empty until the league starts
+}
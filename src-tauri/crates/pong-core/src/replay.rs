@@ -0,0 +1,383 @@
+// Match reducer.
+// Rebuilds the whole match state from its event log, so the score columns
+// stored on `matches` are only a cache of what the events say.
+// Side switches are derived here too (ITTF: after each game, and at 5 points
+// in the deciding game), so undoing the point that caused one removes it.
+// Handicap starts work the same way: every game opens with generated handicap events.
+
+use crate::models::{CourtSides, GameScore, Match, MatchEvent, MatchEventType, MatchRules, ScoreSnapshot};
+use crate::scoring::rules_for_match;
+use crate::serve::{compute_serve_state, ServeInput, ServeState};
+
+#[derive(Debug, Clone)]
+pub struct MatchState {
+    pub score: ScoreSnapshot, // Current game (final game once finished)
+    pub games: Vec<GameScore>,
+    pub status: String,
+    pub winner: Option<String>, // 'p1' or 'p2'
+    pub serve: ServeState,
+    pub court_sides: CourtSides,
+    pub events: Vec<MatchEvent>, // Same events with their score snapshots recomputed
+}
+
+// 'p1' for player1/player3, 'p2' for player2/player4
+pub fn side_of(m: &Match, player_id: i64) -> Option<&'static str> {
+    if m.player1_id == player_id || m.player3_id == Some(player_id) {
+        Some("p1")
+    } else if m.player2_id == player_id || m.player4_id == Some(player_id) {
+        Some("p2")
+    } else {
+        None
+    }
+}
+
+// Starting score of every game, with one event per side that gets points
+fn handicap_start(m: &Match, match_rules: &MatchRules, timestamp: i64) -> (ScoreSnapshot, Vec<MatchEvent>) {
+    let mut score = ScoreSnapshot { p1: 0, p2: 0 };
+    let mut events = Vec::new();
+    for (points, player_id, is_p1) in [
+        (match_rules.handicap_p1, m.player1_id, true),
+        (match_rules.handicap_p2, m.player2_id, false),
+    ] {
+        if points <= 0 {
+            continue;
+        }
+        if is_p1 { score.p1 = points; } else { score.p2 = points; }
+        events.push(MatchEvent {
+            event_type: MatchEventType::Handicap,
+            element_id: Some(player_id.to_string()),
+            timestamp,
+            score_snapshot: score.clone(),
+            forfeit_reason: None,
+        });
+    }
+    (score, events)
+}
+
+pub fn replay(m: &Match, match_rules: &MatchRules, events: &[MatchEvent]) -> MatchState {
+    let rules = rules_for_match(match_rules);
+    let start_timestamp = events.first().map_or(m.start_time.timestamp_millis(), |e| e.timestamp);
+    let (mut score, mut replayed) = handicap_start(m, match_rules, start_timestamp);
+    let mut games: Vec<GameScore> = Vec::new();
+    let mut winner: Option<String> = None;
+    replayed.reserve(events.len());
+    let deciding_game = match_rules.games_to_win * 2 - 1;
+    let mut switches = 0;
+    let mut decider_switched = false;
+    let mut decider_switch_at = None; // Rallies played when it happened, for the doubles serve order
+    let handicap_points = match_rules.handicap_p1.max(0) + match_rules.handicap_p2.max(0);
+
+    for event in events {
+        // Stored side switches and handicaps are dropped and regenerated from the score
+        if matches!(event.event_type, MatchEventType::SideSwitch | MatchEventType::Handicap) {
+            continue;
+        }
+
+        let mut event = event.clone();
+        let mut switch_ends = false;
+        let mut new_game = false;
+        let side = event
+            .element_id
+            .as_deref()
+            .and_then(|id| id.parse::<i64>().ok())
+            .and_then(|id| side_of(m, id));
+
+        // Points recorded after the match was decided are kept but never scored
+        let scores = event.event_type.is_point() && winner.is_none();
+        if let (true, Some(side)) = (scores, side) {
+            if side == "p1" { score.p1 += 1; } else { score.p2 += 1; }
+            event.score_snapshot = score.clone();
+
+            if let Some(w) = rules.game_winner(score.p1, score.p2) {
+                games.push(GameScore {
+                    game_number: games.len() as i64 + 1,
+                    p1: score.p1,
+                    p2: score.p2,
+                    winner: w.clone(),
+                });
+                let won = games.iter().filter(|g| g.winner == w).count() as i64;
+                if won >= match_rules.games_to_win {
+                    winner = Some(w);
+                } else {
+                    // Next game starts at 0-0 (or the handicap), on the other end
+                    score = ScoreSnapshot { p1: 0, p2: 0 };
+                    switch_ends = true;
+                    new_game = true;
+                }
+            } else if games.len() as i64 + 1 == deciding_game && !decider_switched && score.p1.max(score.p2) >= 5 {
+                // At or past 5: a handicap start of 5 or more switches after the first rally
+                decider_switched = true;
+                decider_switch_at = Some(score.p1 + score.p2 - handicap_points);
+                switch_ends = true;
+            }
+        } else {
+            event.score_snapshot = score.clone();
+            // A forfeit hands the match to the other side, the partial score stays as it is
+            if let (MatchEventType::Forfeit, None, Some(side)) = (event.event_type, &winner, side) {
+                winner = Some(if side == "p1" { "p2" } else { "p1" }.to_string());
+            }
+        }
+
+        let timestamp = event.timestamp;
+        replayed.push(event);
+        if switch_ends {
+            switches += 1;
+            replayed.push(MatchEvent {
+                event_type: MatchEventType::SideSwitch,
+                element_id: None,
+                timestamp,
+                score_snapshot: score.clone(),
+                forfeit_reason: None,
+            });
+        }
+        if new_game {
+            let (start, handicap_events) = handicap_start(m, match_rules, timestamp);
+            score = start;
+            replayed.extend(handicap_events);
+        }
+    }
+
+    // Team 1 starts on the left
+    let court_sides = if switches % 2 == 0 {
+        CourtSides { left: "p1".to_string(), right: "p2".to_string() }
+    } else {
+        CourtSides { left: "p2".to_string(), right: "p1".to_string() }
+    };
+
+    // A cancelled match stays cancelled and has no result, even if it had been decided
+    let abandoned = m.status == "abandoned";
+    let winner = winner.filter(|_| !abandoned);
+    let status = if abandoned {
+        "abandoned".to_string()
+    } else if winner.is_some() {
+        "finished".to_string()
+    } else {
+        "in_progress".to_string()
+    };
+
+    let serve = compute_serve_state(&ServeInput {
+        player1_id: m.player1_id,
+        player2_id: m.player2_id,
+        player3_id: m.player3_id,
+        player4_id: m.player4_id,
+        first_server_id: if status == "in_progress" { match_rules.first_server_id } else { None },
+        score_p1: score.p1,
+        score_p2: score.p2,
+        handicap_points,
+        games_played: games.len() as i64,
+        points_to_win: match_rules.points_to_win,
+        serves_before_change: match_rules.serves_before_change,
+        serves_in_deuce: match_rules.serves_in_deuce,
+        is_deuce: rules.is_deuce(score.p1, score.p2),
+        decider_switch_at,
+    });
+
+    MatchState {
+        score,
+        games,
+        status,
+        winner,
+        serve,
+        court_sides,
+        events: replayed,
+    }
+}
+
+// Builds a point-by-point log that ends on the given game scores, for results entered by hand.
+// The trailing side always takes the next point so neither side pulls clear early,
+// and the winner's last point is played last.
+// Whether the scores are reachable under the match rules is left to `replay`.
+pub fn events_for_result(m: &Match, match_rules: &MatchRules, games: &[ScoreSnapshot], timestamp: i64) -> Vec<MatchEvent> {
+    let mut events = Vec::new();
+    let point = |player_id: i64| MatchEvent {
+        event_type: MatchEventType::Point,
+        element_id: Some(player_id.to_string()),
+        timestamp,
+        score_snapshot: ScoreSnapshot { p1: 0, p2: 0 }, // Filled in by the reducer
+        forfeit_reason: None,
+    };
+
+    for game in games {
+        // (player, score reached, handicap start) for the winner and the loser
+        let (winner, loser) = if game.p1 >= game.p2 {
+            ((m.player1_id, game.p1, match_rules.handicap_p1), (m.player2_id, game.p2, match_rules.handicap_p2))
+        } else {
+            ((m.player2_id, game.p2, match_rules.handicap_p2), (m.player1_id, game.p1, match_rules.handicap_p1))
+        };
+        let (mut winner_score, mut loser_score) = (winner.2.max(0), loser.2.max(0));
+
+        while winner_score < winner.1 - 1 || loser_score < loser.1 {
+            if loser_score < loser.1 && (loser_score <= winner_score || winner_score >= winner.1 - 1) {
+                loser_score += 1;
+                events.push(point(loser.0));
+            } else {
+                winner_score += 1;
+                events.push(point(winner.0));
+            }
+        }
+        events.push(point(winner.0));
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MATCH_RULES_VERSION;
+    use chrono::Utc;
+
+    // Classic scoring to 11, two serves each, player 1 serving first
+    fn rules(games_to_win: i64) -> MatchRules {
+        MatchRules {
+            version: MATCH_RULES_VERSION,
+            points_to_win: 11,
+            serves_before_change: 2,
+            is_deuce_enabled: true,
+            serves_in_deuce: 1,
+            serve_type: "free".to_string(),
+            games_to_win,
+            scoring_type: "classic".to_string(),
+            points_cap: None,
+            first_server_id: Some(1),
+            handicap_p1: 0,
+            handicap_p2: 0,
+        }
+    }
+
+    // Players 1 and 2, with partners 3 and 4 for doubles
+    fn new_match(doubles: bool) -> Match {
+        Match {
+            id: 1,
+            player1_id: 1,
+            player2_id: 2,
+            player3_id: doubles.then_some(3),
+            player4_id: doubles.then_some(4),
+            game_mode_id: 1,
+            status: "in_progress".to_string(),
+            score_p1: 0,
+            score_p2: 0,
+            events: "[]".to_string(),
+            start_time: Utc::now(),
+            end_time: None,
+            winner_id: None,
+            match_rules: "{}".to_string(),
+            games: "[]".to_string(),
+            redo_events: "[]".to_string(),
+            pauses: "[]".to_string(),
+            version: 0,
+        }
+    }
+
+    fn points(player_ids: &[i64]) -> Vec<MatchEvent> {
+        player_ids
+            .iter()
+            .map(|id| MatchEvent {
+                event_type: MatchEventType::Point,
+                element_id: Some(id.to_string()),
+                timestamp: 0,
+                score_snapshot: ScoreSnapshot { p1: 0, p2: 0 },
+                forfeit_reason: None,
+            })
+            .collect()
+    }
+
+    fn score(state: &MatchState) -> (i64, i64) {
+        (state.score.p1, state.score.p2)
+    }
+
+    #[test]
+    fn score_and_winner_come_from_the_events() {
+        let m = new_match(false);
+        let mut events = points(&[1; 10]);
+        events.extend(points(&[2, 2, 2]));
+
+        let state = replay(&m, &rules(1), &events);
+        assert_eq!(score(&state), (10, 3));
+        assert_eq!((state.status.as_str(), state.winner.as_deref()), ("in_progress", None));
+        assert_eq!(state.events.last().map(|e| (e.score_snapshot.p1, e.score_snapshot.p2)), Some((10, 3)));
+        assert_eq!(state.serve.current_server_id, Some(1));
+
+        events.extend(points(&[1]));
+        let state = replay(&m, &rules(1), &events);
+        assert_eq!((state.status.as_str(), state.winner.as_deref()), ("finished", Some("p1")));
+        assert_eq!(state.games.len(), 1);
+        assert_eq!(state.serve.current_server_id, None);
+
+        // Points recorded after the match was decided are kept but never scored
+        events.extend(points(&[2]));
+        let state = replay(&m, &rules(1), &events);
+        assert_eq!(score(&state), (11, 3));
+        assert_eq!(state.events.iter().filter(|e| e.event_type.is_point()).count(), events.len());
+    }
+
+    #[test]
+    fn doubles_partners_score_and_undo_for_their_side() {
+        let m = new_match(true);
+        let events = points(&[3, 4, 4, 1]);
+        assert_eq!(score(&replay(&m, &rules(1), &events)), (2, 2));
+
+        // Undo replays the log without its last events, whoever scored them
+        assert_eq!(score(&replay(&m, &rules(1), &events[..3])), (1, 2));
+        assert_eq!(score(&replay(&m, &rules(1), &events[..1])), (1, 0));
+        assert_eq!(score(&replay(&m, &rules(1), &[])), (0, 0));
+    }
+
+    #[test]
+    fn games_and_match_follow_games_to_win() {
+        let m = new_match(false);
+        let mut events = points(&[1; 11]);
+        let state = replay(&m, &rules(2), &events);
+        assert_eq!((state.games.len(), score(&state), state.status.as_str()), (1, (0, 0), "in_progress"));
+
+        events.extend(points(&[2; 11]));
+        events.extend(points(&[1; 11]));
+        let state = replay(&m, &rules(2), &events);
+        let winners: Vec<&str> = state.games.iter().map(|g| g.winner.as_str()).collect();
+        assert_eq!(winners, vec!["p1", "p2", "p1"]);
+        assert_eq!(state.winner.as_deref(), Some("p1"));
+    }
+
+    fn switches(state: &MatchState) -> usize {
+        state.events.iter().filter(|e| e.event_type == MatchEventType::SideSwitch).count()
+    }
+
+    #[test]
+    fn ends_change_after_each_game_and_at_five_in_the_decider() {
+        let m = new_match(false);
+        let mut events = points(&[1; 11]);
+        events.extend(points(&[2; 11]));
+        events.extend(points(&[1, 2, 1, 2, 1, 2, 1, 2]));
+        let state = replay(&m, &rules(2), &events);
+        assert_eq!((switches(&state), state.court_sides.left.as_str()), (2, "p1"));
+
+        events.extend(points(&[1]));
+        let state = replay(&m, &rules(2), &events);
+        assert_eq!((switches(&state), state.court_sides.left.as_str()), (3, "p2"));
+
+        // Only once per decider, and undoing the fifth point takes it back
+        events.extend(points(&[2, 1, 2]));
+        assert_eq!(switches(&replay(&m, &rules(2), &events)), 3);
+        assert_eq!(switches(&replay(&m, &rules(2), &events[..30])), 2);
+    }
+
+    #[test]
+    fn decider_switch_is_not_skipped_by_a_handicap_start() {
+        let m = new_match(false);
+        let handicap = MatchRules { handicap_p2: 6, ..rules(1) };
+        assert_eq!(switches(&replay(&m, &handicap, &[])), 0);
+        let state = replay(&m, &handicap, &points(&[1]));
+        assert_eq!((score(&state), switches(&state)), ((1, 6), 1));
+        assert_eq!(switches(&replay(&m, &handicap, &points(&[1, 2, 2, 1, 1]))), 1);
+    }
+
+    #[test]
+    fn abandoned_matches_stay_abandoned() {
+        let mut m = new_match(false);
+        m.status = "abandoned".to_string();
+        let events = points(&[1; 11]);
+
+        let state = replay(&m, &rules(1), &events);
+        assert_eq!((state.status.as_str(), state.winner.as_deref()), ("abandoned", None));
+        assert_eq!(replay(&m, &rules(1), &events[..5]).status, "abandoned");
+    }
+}
@@ -0,0 +1,1744 @@
+// Every read and write of the app, over one SQLite pool.
+// Match changes go through the event log: read, replay and write happen in one
+// IMMEDIATE transaction, and the write is guarded by the version that was read.
+
+use crate::bracket::{self, DOUBLE_ELIMINATION};
+use crate::error::AppError;
+use crate::handicap;
+use crate::league;
+use crate::live::LiveFeed;
+use crate::models::{
+    BracketMatch, CreateGameModeDto, CreateLeagueDto, CreateTournamentDto, CreateUserDto, ForfeitReason, GameMode, KeyBinding, League, LeagueEntry, LeagueFixture, LeagueTable, Match, MatchAuditEntry,
+    MatchEvent, MatchEventType, MatchResultSnapshot, MatchRules, PauseInterval, PopulatedMatch, RecomputeSummary,
+    ScoreSnapshot, StartMatchDto, Tournament, TournamentBracket, TournamentEntry, User, UserStatistics,
+};
+use crate::replay::{events_for_result, replay, side_of};
+use crate::rules::{self, ARCHIVED_GAME_MODE, TIMEOUTS_PER_SIDE, UNKNOWN_GAME_MODE, UNKNOWN_PLAYER};
+use crate::stats;
+use chrono::Utc;
+use sqlx::{Pool, Sqlite, SqliteConnection, Transaction};
+use std::collections::HashMap;
+
+// How many idempotency keys are kept for each match
+const IDEMPOTENCY_KEYS_PER_MATCH: i64 = 20;
+
+const DEFAULT_GAME_MODES_SQL: &str =
+    "INSERT INTO game_modes (name, points_to_win, serves_before_change, rules_description, is_deuce_enabled, serves_in_deuce, serve_type) VALUES
+    ('Standard 11', 11, 2, 'Classic game to 11 points (2 serves each)', 1, 1, 'free'),
+    ('Classic 21', 21, 5, 'Old school game to 21 points (5 serves each)', 1, 1, 'free')";
+
+const DEFAULT_KEY_BINDINGS_SQL: &str =
+    "INSERT INTO key_bindings (action, key_code, label, is_default) VALUES
+    ('nav_up','ArrowUp','Arrow Up',1),('nav_down','ArrowDown','Arrow Down',1),
+    ('nav_left','ArrowLeft','Arrow Left',1),('nav_right','ArrowRight','Arrow Right',1),
+    ('confirm','Enter','Enter',1),('confirm','Space','Space',1),
+    ('back','Escape','Escape',1),('back','Backspace','Backspace',1),
+    ('add_point_left','KeyA','A',1),('add_point_right','KeyL','L',1),
+    ('undo','KeyZ','Z',1),('redo','KeyY','Y',1),
+    ('add_point_left','Digit1','1',1),('add_point_right','Digit0','0',1)";
+
+#[derive(Clone)]
+pub struct Repository {
+    pool: Pool<Sqlite>,
+    live: LiveFeed,
+}
+
+// --- Helper Functions ---
+
+// IMMEDIATE takes the write lock up front, so concurrent writers queue instead of deadlocking
+async fn begin_write(pool: &Pool<Sqlite>) -> Result<Transaction<'static, Sqlite>, AppError> {
+    Ok(pool.begin_with("BEGIN IMMEDIATE").await?)
+}
+
+async fn fetch_match(conn: &mut SqliteConnection, id: i64) -> Result<Match, AppError> {
+    sqlx::query_as("SELECT * FROM matches WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound(format!("Match {} not found", id)))
+}
+
+// A client that sends the version it last saw gets a conflict when the match has moved on since
+fn check_version(match_data: &Match, expected_version: Option<i64>) -> Result<(), AppError> {
+    match expected_version {
+        Some(version) if version != match_data.version => Err(AppError::conflict()),
+        _ => Ok(()),
+    }
+}
+
+// Response stored for a key that was already applied; the same key on another match or action is an error
+async fn find_idempotent(
+    conn: &mut SqliteConnection,
+    key: Option<&str>,
+    command: &str,
+    match_id: Option<i64>,
+) -> Result<Option<PopulatedMatch>, AppError> {
+    let Some(key) = key else { return Ok(None) };
+    let stored: Option<(String, i64, String)> = sqlx::query_as("SELECT command, match_id, response FROM idempotency_keys WHERE key = ?")
+        .bind(key)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    match stored {
+        None => Ok(None),
+        Some((stored_command, stored_match_id, response)) => {
+            if stored_command != command || match_id.is_some_and(|id| id != stored_match_id) {
+                return Err(AppError::validation("Idempotency key was already used for another action".to_string()));
+            }
+            Ok(Some(serde_json::from_str(&response)?))
+        }
+    }
+}
+
+async fn remember_idempotent(
+    conn: &mut SqliteConnection,
+    key: Option<&str>,
+    command: &str,
+    populated: &PopulatedMatch,
+) -> Result<(), AppError> {
+    let Some(key) = key else { return Ok(()) };
+    let response = serde_json::to_string(populated)?;
+
+    sqlx::query("INSERT INTO idempotency_keys (key, command, match_id, response) VALUES (?, ?, ?, ?)")
+        .bind(key)
+        .bind(command)
+        .bind(populated.id)
+        .bind(response)
+        .execute(&mut *conn)
+        .await?;
+
+    // Only recent keys matter: a double-fired key arrives within moments of the first
+    sqlx::query("DELETE FROM idempotency_keys WHERE match_id = ? AND key NOT IN (SELECT key FROM idempotency_keys WHERE match_id = ? ORDER BY created_at DESC, rowid DESC LIMIT ?)")
+        .bind(populated.id)
+        .bind(populated.id)
+        .bind(IDEMPOTENCY_KEYS_PER_MATCH)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+fn side_players(m: &Match, side: &str) -> Vec<i64> {
+    let (captain, partner) = if side == "p1" { (m.player1_id, m.player3_id) } else { (m.player2_id, m.player4_id) };
+    std::iter::once(captain).chain(partner).collect()
+}
+
+// Adds (sign = 1) or removes (sign = -1) a finished result from the users' counters
+async fn apply_result_counters(conn: &mut SqliteConnection, m: &Match, winner_side: &str, sign: i64) -> Result<(), AppError> {
+    let loser_side = if winner_side == "p1" { "p2" } else { "p1" };
+
+    for pid in side_players(m, winner_side) {
+        sqlx::query("UPDATE users SET wins = wins + ?, matches_played = matches_played + ? WHERE id = ?")
+            .bind(sign)
+            .bind(sign)
+            .bind(pid)
+            .execute(&mut *conn)
+            .await?;
+    }
+    for pid in side_players(m, loser_side) {
+        sqlx::query("UPDATE users SET matches_played = matches_played + ? WHERE id = ?")
+            .bind(sign)
+            .bind(pid)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+// Whether replaying the stored events would change any derived column
+fn replay_changes(match_data: &Match, events: &[MatchEvent]) -> Result<bool, AppError> {
+    let match_rules: MatchRules = serde_json::from_str(&match_data.match_rules)?;
+    let derived = replay(match_data, &match_rules, events);
+    let winner_id = derived.winner.as_deref().map(|w| if w == "p1" { match_data.player1_id } else { match_data.player2_id });
+    Ok(derived.status != match_data.status
+        || (derived.score.p1, derived.score.p2) != (match_data.score_p1, match_data.score_p2)
+        || winner_id != match_data.winner_id
+        || serde_json::to_string(&derived.games)? != match_data.games
+        || serde_json::to_string(&derived.events)? != match_data.events)
+}
+
+// Replays `events`, stores the derived state (and the redo stack) and keeps user counters in step with the result
+async fn apply_events(conn: &mut SqliteConnection, mut match_data: Match, events: Vec<MatchEvent>) -> Result<Match, AppError> {
+    let match_rules: MatchRules = serde_json::from_str(&match_data.match_rules)?;
+    let derived = replay(&match_data, &match_rules, &events);
+
+    let old_winner = match (match_data.status.as_str(), match_data.winner_id) {
+        ("finished", Some(wid)) => side_of(&match_data, wid),
+        _ => None,
+    };
+    let new_winner = derived.winner.as_deref();
+
+    if old_winner != new_winner {
+        if let Some(w) = old_winner {
+            apply_result_counters(&mut *conn, &match_data, w, -1).await?;
+        }
+        if let Some(w) = new_winner {
+            apply_result_counters(&mut *conn, &match_data, w, 1).await?;
+        }
+    }
+
+    // Winner ID is the 'Captain' (P1 or P2) for database tracking of "Winning Side"
+    match_data.winner_id = new_winner.map(|w| if w == "p1" { match_data.player1_id } else { match_data.player2_id });
+    match_data.end_time = match (new_winner, match_data.end_time) {
+        (Some(_), Some(end)) if old_winner.is_some() => Some(end),
+        (Some(_), _) => Some(Utc::now()),
+        (None, _) => None,
+    };
+    match_data.status = derived.status;
+    match_data.score_p1 = derived.score.p1;
+    match_data.score_p2 = derived.score.p2;
+    match_data.games = serde_json::to_string(&derived.games)?;
+    match_data.events = serde_json::to_string(&derived.events)?;
+
+    // Only write over the version that was read, a concurrent write makes this a no-op
+    let result = sqlx::query("UPDATE matches SET score_p1=?, score_p2=?, events=?, games=?, redo_events=?, status=?, end_time=?, winner_id=?, version=version+1 WHERE id=? AND version=?")
+        .bind(match_data.score_p1)
+        .bind(match_data.score_p2)
+        .bind(&match_data.events)
+        .bind(&match_data.games)
+        .bind(&match_data.redo_events)
+        .bind(&match_data.status)
+        .bind(match_data.end_time)
+        .bind(match_data.winner_id)
+        .bind(match_data.id)
+        .bind(match_data.version)
+        .execute(&mut *conn)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict());
+    }
+    match_data.version += 1;
+
+    Ok(match_data)
+}
+
+async fn append_event(
+    conn: &mut SqliteConnection,
+    match_data: Match,
+    event_type: MatchEventType,
+    player_id: Option<i64>,
+) -> Result<Match, AppError> {
+    let event = MatchEvent {
+        event_type,
+        element_id: player_id.map(|id| id.to_string()),
+        timestamp: Utc::now().timestamp_millis(),
+        score_snapshot: ScoreSnapshot { p1: 0, p2: 0 }, // Filled in by the reducer
+        forfeit_reason: None,
+    };
+    push_event(conn, match_data, event).await
+}
+
+async fn push_event(conn: &mut SqliteConnection, mut match_data: Match, event: MatchEvent) -> Result<Match, AppError> {
+    let mut events: Vec<MatchEvent> = serde_json::from_str(&match_data.events).unwrap_or_default();
+    events.push(event);
+
+    // A new event makes the undone ones unreachable
+    match_data.redo_events = "[]".to_string();
+
+    apply_events(conn, match_data, events).await
+}
+
+async fn save_pauses(conn: &mut SqliteConnection, mut match_data: Match, pauses: &[PauseInterval]) -> Result<Match, AppError> {
+    match_data.pauses = serde_json::to_string(pauses)?;
+
+    let result = sqlx::query("UPDATE matches SET pauses = ?, version = version + 1 WHERE id = ? AND version = ?")
+        .bind(&match_data.pauses)
+        .bind(match_data.id)
+        .bind(match_data.version)
+        .execute(&mut *conn)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict());
+    }
+    match_data.version += 1;
+
+    Ok(match_data)
+}
+
+fn result_snapshot(m: &Match) -> Result<MatchResultSnapshot, AppError> {
+    Ok(MatchResultSnapshot {
+        games: serde_json::from_str(&m.games)?,
+        winner_id: m.winner_id,
+        events: serde_json::from_str(&m.events)?,
+    })
+}
+
+async fn validate_lineup(
+    conn: &mut SqliteConnection,
+    player1_id: i64,
+    player2_id: i64,
+    player3_id: Option<i64>,
+    player4_id: Option<i64>,
+) -> Result<(), AppError> {
+    rules::check_lineup(player1_id, player2_id, player3_id, player4_id)?;
+
+    let ids = [Some(player1_id), Some(player2_id), player3_id, player4_id];
+    for id in ids.into_iter().flatten() {
+        let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM users WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::invalid(UNKNOWN_PLAYER, format!("User {} does not exist", id)));
+        }
+    }
+    Ok(())
+}
+
+// Validates the lineup and game mode, then inserts the match
+async fn insert_match(conn: &mut SqliteConnection, new_match: StartMatchDto) -> Result<Match, AppError> {
+    let StartMatchDto { player1_id, player2_id, player3_id, player4_id, game_mode_id, .. } = new_match;
+    validate_lineup(&mut *conn, player1_id, player2_id, player3_id, player4_id).await?;
+
+    let gm: GameMode = sqlx::query_as("SELECT * FROM game_modes WHERE id = ?")
+        .bind(game_mode_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::invalid(UNKNOWN_GAME_MODE, format!("Game mode {} does not exist", game_mode_id)))?;
+
+    if gm.archived {
+        return Err(AppError::invalid(ARCHIVED_GAME_MODE, format!("Game mode {} is archived", game_mode_id)));
+    }
+
+    let mut match_rules = rules::snapshot_rules(&gm);
+    if let Some(sid) = new_match.serves_in_deuce {
+        match_rules.serves_in_deuce = sid;
+    }
+    if let Some(st) = new_match.serve_type {
+        match_rules.serve_type = st;
+    }
+    match_rules.handicap_p1 = new_match.handicap_p1.unwrap_or(0);
+    match_rules.handicap_p2 = new_match.handicap_p2.unwrap_or(0);
+    rules::validate_handicap(&match_rules)?;
+
+    let rules_json = serde_json::to_string(&match_rules)?;
+
+    let result = sqlx::query(
+        "INSERT INTO matches (player1_id, player2_id, player3_id, player4_id, game_mode_id, status, match_rules, events) VALUES (?, ?, ?, ?, ?, 'in_progress', ?, '[]')"
+    )
+    .bind(player1_id)
+    .bind(player2_id)
+    .bind(player3_id)
+    .bind(player4_id)
+    .bind(game_mode_id)
+    .bind(rules_json)
+    .execute(&mut *conn)
+    .await?;
+
+    let id = result.last_insert_rowid();
+    log::info!("Match started with ID: {}", id);
+
+    // Let the reducer write the opening state (handicap events and starting score)
+    let match_data = fetch_match(&mut *conn, id).await?;
+    let match_data = apply_events(&mut *conn, match_data, Vec::new()).await?;
+    Ok(match_data)
+}
+
+// For competitions, whose matches are started later with the game mode chosen up front
+async fn check_game_mode(conn: &mut SqliteConnection, game_mode_id: i64) -> Result<(), AppError> {
+    let archived: bool = sqlx::query_scalar("SELECT archived FROM game_modes WHERE id = ?")
+        .bind(game_mode_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::invalid(UNKNOWN_GAME_MODE, format!("Game mode {} does not exist", game_mode_id)))?;
+    if archived {
+        return Err(AppError::invalid(ARCHIVED_GAME_MODE, format!("Game mode {} is archived", game_mode_id)));
+    }
+    Ok(())
+}
+
+async fn fetch_tournament(conn: &mut SqliteConnection, id: i64) -> Result<Tournament, AppError> {
+    sqlx::query_as("SELECT * FROM tournaments WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound(format!("Tournament {} not found", id)))
+}
+
+// Re-derives every slot of a bracket from its seeds and the linked match results, so a
+// corrected or undone result moves everyone after it. Links are kept: a linked match between
+// players the slot no longer holds is ignored, and counts again once a redo restores them.
+async fn rebuild_bracket(conn: &mut SqliteConnection, tournament_id: i64) -> Result<(), AppError> {
+    let tournament = fetch_tournament(&mut *conn, tournament_id).await?;
+    let slots: Vec<BracketMatch> = sqlx::query_as("SELECT * FROM tournament_matches WHERE tournament_id = ? ORDER BY id")
+        .bind(tournament_id)
+        .fetch_all(&mut *conn)
+        .await?;
+    let seeded: Vec<i64> = sqlx::query_scalar("SELECT user_id FROM tournament_entries WHERE tournament_id = ? AND seed IS NOT NULL ORDER BY seed")
+        .bind(tournament_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let mut plan = Vec::with_capacity(slots.len());
+    // Linked match id -> (player 1, player 2, winner once finished)
+    let mut results: HashMap<i64, (i64, i64, Option<i64>)> = HashMap::new();
+    for slot in &slots {
+        plan.push(bracket::SlotPlan {
+            key: bracket::SlotKey::new(&slot.bracket, slot.round, slot.position),
+            player1: serde_json::from_str(&slot.player1_source)?,
+            player2: serde_json::from_str(&slot.player2_source)?,
+        });
+        if let Some(match_id) = slot.match_id {
+            let m = fetch_match(&mut *conn, match_id).await?;
+            let winner = m.winner_id.filter(|_| m.status == "finished");
+            results.insert(match_id, (m.player1_id, m.player2_id, winner));
+        }
+    }
+
+    let played_by = |match_id: Option<i64>, a: i64, b: i64| {
+        let (p1, p2, winner) = *results.get(&match_id?)?;
+        ((p1, p2) == (a, b) || (p1, p2) == (b, a)).then_some(winner)
+    };
+    let states = bracket::resolve(&plan, &seeded, |i, a, b| played_by(slots[i].match_id, a, b).flatten());
+
+    for (slot, state) in slots.iter().zip(&states) {
+        sqlx::query("UPDATE tournament_matches SET player1_id = ?, player2_id = ?, winner_id = ? WHERE id = ?")
+            .bind(state.player1)
+            .bind(state.player2)
+            .bind(state.winner())
+            .bind(slot.id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    if tournament.status != "registration" {
+        let champion = bracket::champion(&states);
+        sqlx::query("UPDATE tournaments SET status = ?, winner_id = ? WHERE id = ?")
+            .bind(if champion.is_some() { "finished" } else { "in_progress" })
+            .bind(champion)
+            .bind(tournament_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn fetch_league(conn: &mut SqliteConnection, id: i64) -> Result<League, AppError> {
+    sqlx::query_as("SELECT * FROM leagues WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound(format!("League {} not found", id)))
+}
+
+// Fixtures with their status read off the linked match; callers add the WHERE clause
+const LEAGUE_FIXTURES_SQL: &str =
+    "SELECT f.*,
+            CASE WHEN m.status IN ('in_progress', 'finished') THEN m.status ELSE 'pending' END AS status,
+            CASE WHEN m.status = 'finished' THEN m.winner_id END AS winner_id
+     FROM league_fixtures f LEFT JOIN matches m ON m.id = f.match_id";
+
+// A started league is finished once every fixture has a finished match, and reopens when one
+// of them is undone or cancelled
+async fn refresh_league(conn: &mut SqliteConnection, league_id: i64) -> Result<(), AppError> {
+    let league = fetch_league(&mut *conn, league_id).await?;
+    if league.status == "registration" {
+        return Ok(());
+    }
+    let unfinished: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM league_fixtures f LEFT JOIN matches m ON m.id = f.match_id
+         WHERE f.league_id = ? AND (m.id IS NULL OR m.status != 'finished')"
+    )
+    .bind(league_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE leagues SET status = ? WHERE id = ?")
+        .bind(if unfinished == 0 { "finished" } else { "in_progress" })
+        .bind(league_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+// Called in every transaction that can change a match result; does nothing for a friendly
+async fn sync_competitions(conn: &mut SqliteConnection, match_id: i64) -> Result<(), AppError> {
+    let tournament_id: Option<i64> = sqlx::query_scalar("SELECT tournament_id FROM tournament_matches WHERE match_id = ?")
+        .bind(match_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(id) = tournament_id {
+        rebuild_bracket(&mut *conn, id).await?;
+    }
+    let league_id: Option<i64> = sqlx::query_scalar("SELECT league_id FROM league_fixtures WHERE match_id = ?")
+        .bind(match_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(id) = league_id {
+        refresh_league(&mut *conn, id).await?;
+    }
+    Ok(())
+}
+
+// Builds the response for a match inside an open transaction, so it can be sent before commit
+async fn populate_match_in(conn: &mut SqliteConnection, match_data: Match) -> Result<PopulatedMatch, AppError> {
+
+    let p1: User = sqlx::query_as("SELECT * FROM users WHERE id = ?")
+        .bind(match_data.player1_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let p2: User = sqlx::query_as("SELECT * FROM users WHERE id = ?")
+        .bind(match_data.player2_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let gm: GameMode = sqlx::query_as("SELECT * FROM game_modes WHERE id = ?")
+        .bind(match_data.game_mode_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let p3: Option<User> = if let Some(p3_id) = match_data.player3_id {
+         sqlx::query_as("SELECT * FROM users WHERE id = ?")
+            .bind(p3_id)
+            .fetch_optional(&mut *conn)
+            .await?
+    } else { None };
+
+    let p4: Option<User> = if let Some(p4_id) = match_data.player4_id {
+         sqlx::query_as("SELECT * FROM users WHERE id = ?")
+            .bind(p4_id)
+            .fetch_optional(&mut *conn)
+            .await?
+    } else { None };
+
+    let events: Vec<MatchEvent> =
+        serde_json::from_str(&match_data.events).unwrap_or_default();
+    let match_rules: MatchRules =
+        serde_json::from_str(&match_data.match_rules).unwrap_or_else(|_| rules::snapshot_rules(&gm));
+
+    let first_server = match_rules.first_server_id;
+
+    // Score, games and server always come from the event log
+    let derived = replay(&match_data, &match_rules, &events);
+    let (games_p1, games_p2) = stats::count_games_won(&derived.games);
+    let redo_events: Vec<MatchEvent> = serde_json::from_str(&match_data.redo_events).unwrap_or_default();
+    let pauses: Vec<PauseInterval> = serde_json::from_str(&match_data.pauses).unwrap_or_default();
+    let active_duration_ms = stats::active_duration_ms(&match_data);
+    // A match that ended during a pause is not paused any more
+    let paused = derived.status == "in_progress" && rules::is_paused(&pauses);
+    let timeouts_used = ScoreSnapshot {
+        p1: rules::count_timeouts(&match_data, &derived.events, "p1"),
+        p2: rules::count_timeouts(&match_data, &derived.events, "p2"),
+    };
+
+    let winner_id = derived.winner.as_deref().map(|w| if w == "p1" { match_data.player1_id } else { match_data.player2_id });
+    let winner: Option<User> = if let Some(wid) = winner_id {
+         sqlx::query_as("SELECT * FROM users WHERE id = ?")
+            .bind(wid)
+            .fetch_optional(&mut *conn)
+            .await?
+    } else {
+        None
+    };
+
+    Ok(PopulatedMatch {
+        id: match_data.id,
+        player1: p1,
+        player2: p2,
+        player3: p3,
+        player4: p4,
+        game_mode: gm,
+        status: derived.status,
+        version: match_data.version,
+        score: derived.score,
+        games: derived.games,
+        games_won: ScoreSnapshot {
+            p1: games_p1,
+            p2: games_p2,
+        },
+        events: derived.events,
+        redo_count: redo_events.len() as i64,
+        court_sides: derived.court_sides,
+        timeouts_used,
+        is_paused: paused,
+        active_duration_ms,
+        match_rules,
+        winner,
+        first_server,
+        current_server_id: derived.serve.current_server_id,
+        next_server_id: derived.serve.next_server_id,
+        serves_remaining: derived.serve.serves_remaining,
+    })
+}
+
+impl Repository {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Repository { pool, live: LiveFeed::default() }
+    }
+
+    pub fn pool(&self) -> &Pool<Sqlite> {
+        &self.pool
+    }
+
+    // Every committed change to a match, from scoring to pauses, edits and cancelling
+    pub fn live(&self) -> &LiveFeed {
+        &self.live
+    }
+
+    pub async fn populate_match(&self, match_data: Match) -> Result<PopulatedMatch, AppError> {
+        let mut conn = self.pool.acquire().await?;
+        populate_match_in(&mut conn, match_data).await
+    }
+
+    async fn populate_all(&self, matches: Vec<Match>) -> Result<Vec<PopulatedMatch>, AppError> {
+        let mut populated = Vec::new();
+        for m in matches {
+            populated.push(self.populate_match(m).await?);
+        }
+        Ok(populated)
+    }
+
+    // --- Users ---
+
+    pub async fn get_users(&self) -> Result<Vec<User>, AppError> {
+        Ok(sqlx::query_as::<_, User>("SELECT * FROM users")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn create_user(&self, user: CreateUserDto) -> Result<User, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO users (name, fun_nickname, avatar, color, icon) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&user.name)
+        .bind(&user.fun_nickname)
+        .bind(user.avatar.unwrap_or_default())
+        .bind(&user.color)
+        .bind(&user.icon)
+        .execute(&self.pool)
+        .await?;
+
+        let id = result.last_insert_rowid();
+        log::info!("User created with ID: {}", id);
+
+        Ok(sqlx::query_as("SELECT * FROM users WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    // Most wins first, fewer matches played breaks ties
+    pub async fn get_leaderboard(&self) -> Result<Vec<User>, AppError> {
+        Ok(sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY wins DESC, matches_played ASC, name ASC")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn update_user(&self, id: i64, name: &str, color: &str, icon: &str) -> Result<User, AppError> {
+        sqlx::query("UPDATE users SET name = ?, color = ?, icon = ? WHERE id = ?")
+            .bind(name)
+            .bind(color)
+            .bind(icon)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query_as("SELECT * FROM users WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(AppError::NotFound(format!("User {} not found", id)))
+    }
+
+    // --- Game Modes ---
+
+    pub async fn get_game_modes(&self) -> Result<Vec<GameMode>, AppError> {
+        Ok(sqlx::query_as::<_, GameMode>("SELECT * FROM game_modes WHERE archived = 0")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn create_game_mode(&self, mode: CreateGameModeDto) -> Result<GameMode, AppError> {
+        rules::validate_game_mode(mode.points_to_win, mode.serves_before_change, mode.serves_in_deuce, mode.games_to_win, &mode.scoring_type, mode.points_cap)?;
+
+        let result = sqlx::query(
+            "INSERT INTO game_modes (name, points_to_win, serves_before_change, rules_description, is_deuce_enabled, serves_in_deuce, serve_type, games_to_win, scoring_type, points_cap) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&mode.name)
+        .bind(mode.points_to_win)
+        .bind(mode.serves_before_change)
+        .bind(mode.rules_description.unwrap_or_default())
+        .bind(mode.is_deuce_enabled)
+        .bind(mode.serves_in_deuce)
+        .bind(&mode.serve_type)
+        .bind(mode.games_to_win)
+        .bind(&mode.scoring_type)
+        .bind(mode.points_cap)
+        .execute(&self.pool)
+        .await?;
+
+        let id = result.last_insert_rowid();
+
+        Ok(sqlx::query_as("SELECT * FROM game_modes WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    // Matches keep their own MatchRules snapshot, so editing a mode only affects new matches
+    pub async fn update_game_mode(&self, id: i64, mode: CreateGameModeDto) -> Result<GameMode, AppError> {
+        rules::validate_game_mode(mode.points_to_win, mode.serves_before_change, mode.serves_in_deuce, mode.games_to_win, &mode.scoring_type, mode.points_cap)?;
+
+        let result = sqlx::query(
+            "UPDATE game_modes SET name = ?, points_to_win = ?, serves_before_change = ?, rules_description = ?, is_deuce_enabled = ?, serves_in_deuce = ?, serve_type = ?, games_to_win = ?, scoring_type = ?, points_cap = ? WHERE id = ?"
+        )
+        .bind(&mode.name)
+        .bind(mode.points_to_win)
+        .bind(mode.serves_before_change)
+        .bind(mode.rules_description.unwrap_or_default())
+        .bind(mode.is_deuce_enabled)
+        .bind(mode.serves_in_deuce)
+        .bind(&mode.serve_type)
+        .bind(mode.games_to_win)
+        .bind(&mode.scoring_type)
+        .bind(mode.points_cap)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("GameMode not found".to_string()));
+        }
+
+        Ok(sqlx::query_as("SELECT * FROM game_modes WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    // Hides a mode from new matches while keeping its history
+    pub async fn archive_game_mode(&self, id: i64) -> Result<GameMode, AppError> {
+        let result = sqlx::query("UPDATE game_modes SET archived = 1 WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("GameMode not found".to_string()));
+        }
+
+        Ok(sqlx::query_as("SELECT * FROM game_modes WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    // Only unused modes can be deleted; modes with matches must be archived instead
+    pub async fn delete_game_mode(&self, id: i64) -> Result<(), AppError> {
+        let used: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM matches WHERE game_mode_id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        if used.0 > 0 {
+            return Err(AppError::RuleViolation(format!("GameMode is used by {} matches, archive it instead", used.0)));
+        }
+
+        let result = sqlx::query("DELETE FROM game_modes WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("GameMode not found".to_string()));
+        }
+        Ok(())
+    }
+
+    // Quick seed for basic game mode if none exists
+    pub async fn ensure_basic_game_mode(&self) -> Result<(), AppError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM game_modes")
+            .fetch_one(&self.pool)
+            .await?;
+
+        if count.0 == 0 {
+            sqlx::query(DEFAULT_GAME_MODES_SQL)
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    // --- Matches ---
+
+    pub async fn start_match(&self, new_match: StartMatchDto, idempotency_key: Option<&str>) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        if let Some(populated) = find_idempotent(&mut tx, idempotency_key, "start_match", None).await? {
+            return Ok(populated);
+        }
+
+        let match_data = insert_match(&mut tx, new_match).await?;
+        let populated = populate_match_in(&mut tx, match_data).await?;
+        remember_idempotent(&mut tx, idempotency_key, "start_match", &populated).await?;
+        tx.commit().await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // Read, replay and write happen in one transaction, so concurrent points queue up
+    pub async fn add_point(
+        &self,
+        match_id: i64,
+        player_id: i64,
+        reason: Option<MatchEventType>,
+        idempotency_key: Option<&str>,
+        expected_version: Option<i64>,
+    ) -> Result<PopulatedMatch, AppError> {
+        let reason = reason.unwrap_or(MatchEventType::Point);
+        if !reason.is_point() {
+            return Err(AppError::validation("A let does not score, use record_let".to_string()));
+        }
+
+        let mut tx = begin_write(&self.pool).await?;
+        // A repeated key gets the original response, even if the match has finished since
+        if let Some(populated) = find_idempotent(&mut tx, idempotency_key, "add_point", Some(match_id)).await? {
+            return Ok(populated);
+        }
+
+        // 1. Fetch Match
+        let match_data = fetch_match(&mut tx, match_id).await?;
+        check_version(&match_data, expected_version)?;
+
+        rules::ensure_in_progress(&match_data)?;
+        rules::ensure_not_paused(&match_data)?;
+
+        // 2. Identify Player side
+        if side_of(&match_data, player_id).is_none() {
+            return Err(AppError::validation("User is not in this match".to_string()));
+        }
+
+        // 3. Append the event, the reducer derives the new score
+        let match_data = append_event(&mut tx, match_data, reason, Some(player_id)).await?;
+        sync_competitions(&mut tx, match_id).await?;
+        let populated = populate_match_in(&mut tx, match_data).await?;
+        remember_idempotent(&mut tx, idempotency_key, "add_point", &populated).await?;
+        tx.commit().await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // Records a let: the rally is replayed and the score does not change
+    pub async fn record_let(&self, match_id: i64, expected_version: Option<i64>) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let match_data = fetch_match(&mut tx, match_id).await?;
+        check_version(&match_data, expected_version)?;
+
+        rules::ensure_in_progress(&match_data)?;
+        rules::ensure_not_paused(&match_data)?;
+
+        let match_data = append_event(&mut tx, match_data, MatchEventType::Let, None).await?;
+        tx.commit().await?;
+        let populated = self.populate_match(match_data).await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // One timeout per side per match
+    pub async fn call_timeout(&self, match_id: i64, player_id: i64, expected_version: Option<i64>) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let match_data = fetch_match(&mut tx, match_id).await?;
+        check_version(&match_data, expected_version)?;
+
+        rules::ensure_in_progress(&match_data)?;
+        rules::ensure_not_paused(&match_data)?;
+
+        let side = side_of(&match_data, player_id).ok_or(AppError::validation("User is not in this match".to_string()))?;
+        let events: Vec<MatchEvent> = serde_json::from_str(&match_data.events).unwrap_or_default();
+        if rules::count_timeouts(&match_data, &events, side) >= TIMEOUTS_PER_SIDE {
+            return Err(AppError::RuleViolation("Timeout already used by this side".to_string()));
+        }
+
+        let match_data = append_event(&mut tx, match_data, MatchEventType::Timeout, Some(player_id)).await?;
+        tx.commit().await?;
+        let populated = self.populate_match(match_data).await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // Ends the match with a win for the other side, keeping the score reached so far.
+    // Allowed while paused: a walkover is usually recorded before anyone plays.
+    pub async fn forfeit_match(
+        &self,
+        match_id: i64,
+        player_id: i64,
+        reason: ForfeitReason,
+        expected_version: Option<i64>,
+    ) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let match_data = fetch_match(&mut tx, match_id).await?;
+        check_version(&match_data, expected_version)?;
+
+        rules::ensure_in_progress(&match_data)?;
+        if side_of(&match_data, player_id).is_none() {
+            return Err(AppError::validation("User is not in this match".to_string()));
+        }
+
+        let now = Utc::now().timestamp_millis();
+        let event = MatchEvent {
+            event_type: MatchEventType::Forfeit,
+            element_id: Some(player_id.to_string()),
+            timestamp: now,
+            score_snapshot: ScoreSnapshot { p1: 0, p2: 0 }, // Filled in by the reducer
+            forfeit_reason: Some(reason),
+        };
+        let match_data = push_event(&mut tx, match_data, event).await?;
+
+        // The match ends now, and a pause still open ends with it
+        let mut pauses: Vec<PauseInterval> = serde_json::from_str(&match_data.pauses).unwrap_or_default();
+        let match_data = match pauses.last_mut() {
+            Some(p) if p.ended_at.is_none() => {
+                p.ended_at = Some(now);
+                save_pauses(&mut tx, match_data, &pauses).await?
+            }
+            _ => match_data,
+        };
+        sync_competitions(&mut tx, match_id).await?;
+        tx.commit().await?;
+        let populated = self.populate_match(match_data).await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    pub async fn undo_last_point(
+        &self,
+        match_id: i64,
+        idempotency_key: Option<&str>,
+        expected_version: Option<i64>,
+    ) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        if let Some(populated) = find_idempotent(&mut tx, idempotency_key, "undo_last_point", Some(match_id)).await? {
+            return Ok(populated);
+        }
+        let mut match_data = fetch_match(&mut tx, match_id).await?;
+        check_version(&match_data, expected_version)?;
+
+        rules::ensure_not_abandoned(&match_data)?;
+        rules::ensure_not_paused(&match_data)?;
+
+        let mut events: Vec<MatchEvent> = serde_json::from_str(&match_data.events).unwrap_or_default();
+        // Side switches and handicaps come from the reducer, undo the event that caused them
+        while events.last().is_some_and(|e| matches!(e.event_type, MatchEventType::SideSwitch | MatchEventType::Handicap)) {
+            events.pop();
+        }
+        let undone = events.pop().ok_or(AppError::RuleViolation("No events to undo".to_string()))?;
+
+        let mut redo_events: Vec<MatchEvent> = serde_json::from_str(&match_data.redo_events).unwrap_or_default();
+        redo_events.push(undone);
+        match_data.redo_events = serde_json::to_string(&redo_events)?;
+
+        let match_data = apply_events(&mut tx, match_data, events).await?;
+        sync_competitions(&mut tx, match_id).await?;
+        let populated = populate_match_in(&mut tx, match_data).await?;
+        remember_idempotent(&mut tx, idempotency_key, "undo_last_point", &populated).await?;
+        tx.commit().await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    pub async fn redo_point(
+        &self,
+        match_id: i64,
+        idempotency_key: Option<&str>,
+        expected_version: Option<i64>,
+    ) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        if let Some(populated) = find_idempotent(&mut tx, idempotency_key, "redo_point", Some(match_id)).await? {
+            return Ok(populated);
+        }
+        let mut match_data = fetch_match(&mut tx, match_id).await?;
+        check_version(&match_data, expected_version)?;
+
+        rules::ensure_not_abandoned(&match_data)?;
+        rules::ensure_not_paused(&match_data)?;
+
+        let mut redo_events: Vec<MatchEvent> = serde_json::from_str(&match_data.redo_events).unwrap_or_default();
+        let redone = redo_events.pop().ok_or(AppError::RuleViolation("No events to redo".to_string()))?;
+        match_data.redo_events = serde_json::to_string(&redo_events)?;
+
+        let mut events: Vec<MatchEvent> = serde_json::from_str(&match_data.events).unwrap_or_default();
+        events.push(redone);
+
+        let match_data = apply_events(&mut tx, match_data, events).await?;
+        sync_competitions(&mut tx, match_id).await?;
+        let populated = populate_match_in(&mut tx, match_data).await?;
+        remember_idempotent(&mut tx, idempotency_key, "redo_point", &populated).await?;
+        tx.commit().await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // Recomputes the cached score columns (and user counters) from the event log
+    pub async fn rebuild_match(&self, id: i64) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let match_data = fetch_match(&mut tx, id).await?;
+
+        let events: Vec<MatchEvent> = serde_json::from_str(&match_data.events)?;
+        let match_data = apply_events(&mut tx, match_data, events).await?;
+        sync_competitions(&mut tx, id).await?;
+        tx.commit().await?;
+        let populated = self.populate_match(match_data).await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    pub async fn pause_match(&self, id: i64, expected_version: Option<i64>) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let match_data = fetch_match(&mut tx, id).await?;
+        check_version(&match_data, expected_version)?;
+
+        rules::ensure_in_progress(&match_data)?;
+
+        let mut pauses: Vec<PauseInterval> = serde_json::from_str(&match_data.pauses).unwrap_or_default();
+        if rules::is_paused(&pauses) {
+            return Err(AppError::RuleViolation("Match is already paused".to_string()));
+        }
+        pauses.push(PauseInterval {
+            started_at: Utc::now().timestamp_millis(),
+            ended_at: None,
+        });
+
+        let match_data = save_pauses(&mut tx, match_data, &pauses).await?;
+        tx.commit().await?;
+        let populated = self.populate_match(match_data).await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    pub async fn resume_match(&self, id: i64, expected_version: Option<i64>) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let match_data = fetch_match(&mut tx, id).await?;
+        check_version(&match_data, expected_version)?;
+        rules::ensure_in_progress(&match_data)?;
+
+        let mut pauses: Vec<PauseInterval> = serde_json::from_str(&match_data.pauses).unwrap_or_default();
+        match pauses.last_mut() {
+            Some(p) if p.ended_at.is_none() => p.ended_at = Some(Utc::now().timestamp_millis()),
+            _ => return Err(AppError::RuleViolation("Match is not paused".to_string())),
+        }
+
+        let match_data = save_pauses(&mut tx, match_data, &pauses).await?;
+        tx.commit().await?;
+        let populated = self.populate_match(match_data).await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // Corrects a finished match, either from the final game scores or from a full event list.
+    // The new result is replayed under the match's own rules, and the counters follow the new winner.
+    pub async fn edit_match_result(
+        &self,
+        match_id: i64,
+        games: Option<Vec<ScoreSnapshot>>,
+        events: Option<Vec<MatchEvent>>,
+        note: Option<String>,
+        expected_version: Option<i64>,
+    ) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let mut match_data = fetch_match(&mut tx, match_id).await?;
+        check_version(&match_data, expected_version)?;
+
+        if match_data.status != "finished" {
+            return Err(AppError::RuleViolation("Only finished matches can be edited".to_string()));
+        }
+
+        let match_rules: MatchRules = serde_json::from_str(&match_data.match_rules)?;
+        let timestamp = match_data.end_time.unwrap_or_else(Utc::now).timestamp_millis();
+        let events = match (games, events) {
+            (Some(games), None) => events_for_result(&match_data, &match_rules, &games, timestamp),
+            (None, Some(events)) => events,
+            _ => return Err(AppError::validation("Provide either the game scores or the event list".to_string())),
+        };
+
+        // Every point must count and the last one must decide the match
+        let derived = replay(&match_data, &match_rules, &events);
+        let handicap_per_game = match_rules.handicap_p1.max(0) + match_rules.handicap_p2.max(0);
+        let scored: i64 = derived.games.iter().map(|g| g.p1 + g.p2 - handicap_per_game).sum();
+        let submitted = events.iter().filter(|e| e.event_type.is_point()).count() as i64;
+        if derived.winner.is_none() || scored != submitted {
+            return Err(AppError::validation("Result is not a valid finished match under this match's rules".to_string()));
+        }
+
+        let before = result_snapshot(&match_data)?;
+        match_data.redo_events = "[]".to_string();
+        let match_data = apply_events(&mut tx, match_data, events).await?;
+        let after = result_snapshot(&match_data)?;
+
+        sqlx::query("INSERT INTO match_audit (match_id, action, before, after, note) VALUES (?, 'edit_result', ?, ?, ?)")
+            .bind(match_id)
+            .bind(serde_json::to_string(&before)?)
+            .bind(serde_json::to_string(&after)?)
+            .bind(note)
+            .execute(&mut *tx)
+            .await?;
+        sync_competitions(&mut tx, match_id).await?;
+
+        tx.commit().await?;
+        let populated = self.populate_match(match_data).await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    pub async fn get_match_audit(&self, match_id: i64) -> Result<Vec<MatchAuditEntry>, AppError> {
+        Ok(sqlx::query_as("SELECT * FROM match_audit WHERE match_id = ? ORDER BY created_at DESC, id DESC")
+            .bind(match_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn get_match(&self, id: i64) -> Result<PopulatedMatch, AppError> {
+        let mut conn = self.pool.acquire().await?;
+        let match_data = fetch_match(&mut conn, id).await?;
+        populate_match_in(&mut conn, match_data).await
+    }
+
+    pub async fn get_user_matches(&self, user_id: i64) -> Result<Vec<PopulatedMatch>, AppError> {
+        let matches: Vec<Match> = sqlx::query_as(
+            "SELECT * FROM matches WHERE player1_id = ? OR player2_id = ? OR player3_id = ? OR player4_id = ? ORDER BY start_time DESC"
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.populate_all(matches).await
+    }
+
+    // Newest first, optionally only one status ('in_progress', 'finished', 'abandoned')
+    pub async fn list_matches(&self, status: Option<&str>, limit: i64) -> Result<Vec<PopulatedMatch>, AppError> {
+        let matches: Vec<Match> = sqlx::query_as(
+            "SELECT * FROM matches WHERE ? IS NULL OR status = ? ORDER BY start_time DESC, id DESC LIMIT ?"
+        )
+        .bind(status)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.populate_all(matches).await
+    }
+
+    pub async fn set_first_server(&self, id: i64, first_server_id: i64, expected_version: Option<i64>) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let mut match_data = fetch_match(&mut tx, id).await?;
+        check_version(&match_data, expected_version)?;
+        // The serve rotation of a finished or abandoned match is part of its record
+        rules::ensure_in_progress(&match_data)?;
+        if side_of(&match_data, first_server_id).is_none() {
+            return Err(AppError::validation("User is not in this match".to_string()));
+        }
+
+        let mut match_rules: MatchRules = serde_json::from_str(&match_data.match_rules)?;
+
+        match_rules.first_server_id = Some(first_server_id);
+        match_data.match_rules = serde_json::to_string(&match_rules)?;
+
+        let result = sqlx::query("UPDATE matches SET match_rules = ?, version = version + 1 WHERE id = ? AND version = ?")
+            .bind(&match_data.match_rules)
+            .bind(id)
+            .bind(match_data.version)
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::conflict());
+        }
+        match_data.version += 1;
+        tx.commit().await?;
+
+        let populated = self.populate_match(match_data).await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // A finished result already counts for the players, it is corrected with edit_match_result
+    pub async fn cancel_match(&self, id: i64, expected_version: Option<i64>) -> Result<(), AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let match_data = fetch_match(&mut tx, id).await?;
+        check_version(&match_data, expected_version)?;
+        if match_data.status == "finished" {
+            return Err(AppError::RuleViolation("A finished match can't be cancelled, edit its result instead".to_string()));
+        }
+
+        let result = sqlx::query("UPDATE matches SET status = 'abandoned', version = version + 1 WHERE id = ? AND version = ?")
+            .bind(id)
+            .bind(match_data.version)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::conflict());
+        }
+        sync_competitions(&mut tx, id).await?;
+        tx.commit().await?;
+        // Spectators see the match end as abandoned
+        self.live.publish(&self.get_match(id).await?);
+        Ok(())
+    }
+
+    pub async fn get_open_matches(&self) -> Result<Vec<PopulatedMatch>, AppError> {
+        let matches: Vec<Match> = sqlx::query_as(
+            "SELECT * FROM matches WHERE status = 'in_progress' ORDER BY start_time DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.populate_all(matches).await
+    }
+
+    // --- Tournaments ---
+
+    pub async fn create_tournament(&self, dto: CreateTournamentDto) -> Result<Tournament, AppError> {
+        let CreateTournamentDto { name, game_mode_id, format, grand_final_reset } = dto;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::validation("Tournament name is required".to_string()));
+        }
+        if !bracket::FORMATS.contains(&format.as_str()) {
+            return Err(AppError::validation(format!("Unknown tournament format: {}", format)));
+        }
+        let mut tx = begin_write(&self.pool).await?;
+        check_game_mode(&mut tx, game_mode_id).await?;
+
+        let result = sqlx::query("INSERT INTO tournaments (name, format, game_mode_id, grand_final_reset) VALUES (?, ?, ?, ?)")
+            .bind(name)
+            .bind(&format)
+            .bind(game_mode_id)
+            .bind(grand_final_reset && format == DOUBLE_ELIMINATION)
+            .execute(&mut *tx)
+            .await?;
+        let tournament = fetch_tournament(&mut tx, result.last_insert_rowid()).await?;
+        tx.commit().await?;
+        Ok(tournament)
+    }
+
+    pub async fn get_tournaments(&self) -> Result<Vec<Tournament>, AppError> {
+        Ok(sqlx::query_as("SELECT * FROM tournaments ORDER BY created_at DESC, id DESC")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn register_tournament_player(&self, tournament_id: i64, user_id: i64) -> Result<TournamentBracket, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let tournament = fetch_tournament(&mut tx, tournament_id).await?;
+        if tournament.status != "registration" {
+            return Err(AppError::RuleViolation("Tournament has already started".to_string()));
+        }
+        let user: Option<(i64,)> = sqlx::query_as("SELECT id FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if user.is_none() {
+            return Err(AppError::invalid(UNKNOWN_PLAYER, format!("User {} does not exist", user_id)));
+        }
+
+        let result = sqlx::query("INSERT INTO tournament_entries (tournament_id, user_id) VALUES (?, ?) ON CONFLICT DO NOTHING")
+            .bind(tournament_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::validation("User is already registered".to_string()));
+        }
+        tx.commit().await?;
+        self.get_bracket(tournament_id).await
+    }
+
+    // Seeds the entries by win rate and draws the whole bracket; byes are decided straight away
+    pub async fn start_tournament(&self, tournament_id: i64) -> Result<TournamentBracket, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let tournament = fetch_tournament(&mut tx, tournament_id).await?;
+        if tournament.status != "registration" {
+            return Err(AppError::RuleViolation("Tournament has already started".to_string()));
+        }
+        let players: Vec<User> = sqlx::query_as(
+            "SELECT u.* FROM tournament_entries e JOIN users u ON u.id = e.user_id WHERE e.tournament_id = ? ORDER BY e.id"
+        )
+        .bind(tournament_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        if players.len() < 2 {
+            return Err(AppError::validation("A tournament needs at least 2 players".to_string()));
+        }
+
+        let seeded = bracket::seed_order(&players);
+        for (i, user_id) in seeded.iter().enumerate() {
+            sqlx::query("UPDATE tournament_entries SET seed = ? WHERE tournament_id = ? AND user_id = ?")
+                .bind(i as i64 + 1)
+                .bind(tournament_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for slot in bracket::plan(&tournament.format, seeded.len(), tournament.grand_final_reset) {
+            sqlx::query("INSERT INTO tournament_matches (tournament_id, bracket, round, position, player1_source, player2_source) VALUES (?, ?, ?, ?, ?, ?)")
+                .bind(tournament_id)
+                .bind(&slot.key.bracket)
+                .bind(slot.key.round)
+                .bind(slot.key.position)
+                .bind(serde_json::to_string(&slot.player1)?)
+                .bind(serde_json::to_string(&slot.player2)?)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("UPDATE tournaments SET status = 'in_progress' WHERE id = ?")
+            .bind(tournament_id)
+            .execute(&mut *tx)
+            .await?;
+        rebuild_bracket(&mut tx, tournament_id).await?;
+        tx.commit().await?;
+        self.get_bracket(tournament_id).await
+    }
+
+    pub async fn get_bracket(&self, tournament_id: i64) -> Result<TournamentBracket, AppError> {
+        let mut conn = self.pool.acquire().await?;
+        let tournament = fetch_tournament(&mut conn, tournament_id).await?;
+        let entries: Vec<TournamentEntry> = sqlx::query_as(
+            "SELECT * FROM tournament_entries WHERE tournament_id = ? ORDER BY seed IS NULL, seed, id"
+        )
+        .bind(tournament_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        let matches: Vec<BracketMatch> = sqlx::query_as(
+            "SELECT * FROM tournament_matches WHERE tournament_id = ? ORDER BY id"
+        )
+        .bind(tournament_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        Ok(TournamentBracket { tournament, entries, matches })
+    }
+
+    // Starts the earliest slot whose players are known, through the same path as start_match.
+    // A slot whose match was cancelled can be started again.
+    pub async fn start_next_bracket_match(&self, tournament_id: i64) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let tournament = fetch_tournament(&mut tx, tournament_id).await?;
+        if tournament.status != "in_progress" {
+            return Err(AppError::RuleViolation("Tournament is not in progress".to_string()));
+        }
+        // A slot is ready when it has no usable match: none linked, an abandoned one, or one
+        // between players the slot no longer holds (starting a new match replaces the link)
+        let slot: BracketMatch = sqlx::query_as(
+            "SELECT t.* FROM tournament_matches t LEFT JOIN matches m ON m.id = t.match_id
+             WHERE t.tournament_id = ? AND t.winner_id IS NULL AND t.player1_id IS NOT NULL AND t.player2_id IS NOT NULL
+               AND (t.match_id IS NULL OR m.status = 'abandoned'
+                    OR NOT ((m.player1_id = t.player1_id AND m.player2_id = t.player2_id)
+                         OR (m.player1_id = t.player2_id AND m.player2_id = t.player1_id)))
+             ORDER BY t.id LIMIT 1"
+        )
+        .bind(tournament_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::RuleViolation("No bracket match is ready to start".to_string()))?;
+
+        let match_data = insert_match(&mut tx, StartMatchDto {
+            player1_id: slot.player1_id.unwrap_or_default(),
+            player2_id: slot.player2_id.unwrap_or_default(),
+            game_mode_id: tournament.game_mode_id,
+            ..Default::default()
+        }).await?;
+        sqlx::query("UPDATE tournament_matches SET match_id = ? WHERE id = ?")
+            .bind(match_data.id)
+            .bind(slot.id)
+            .execute(&mut *tx)
+            .await?;
+        let populated = populate_match_in(&mut tx, match_data).await?;
+        tx.commit().await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // --- Leagues ---
+
+    pub async fn create_league(&self, dto: CreateLeagueDto) -> Result<League, AppError> {
+        let CreateLeagueDto { name, game_mode_id, double_round, tie_breakers } = dto;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::validation("League name is required".to_string()));
+        }
+        for (i, tie_breaker) in tie_breakers.iter().enumerate() {
+            if !league::TIE_BREAKERS.contains(&tie_breaker.as_str()) {
+                return Err(AppError::validation(format!("Unknown tie-breaker: {}", tie_breaker)));
+            }
+            if tie_breakers[..i].contains(tie_breaker) {
+                return Err(AppError::validation(format!("Tie-breaker {} is listed twice", tie_breaker)));
+            }
+        }
+        let mut tx = begin_write(&self.pool).await?;
+        check_game_mode(&mut tx, game_mode_id).await?;
+
+        let result = sqlx::query("INSERT INTO leagues (name, game_mode_id, double_round, tie_breakers) VALUES (?, ?, ?, ?)")
+            .bind(name)
+            .bind(game_mode_id)
+            .bind(double_round)
+            .bind(serde_json::to_string(&tie_breakers)?)
+            .execute(&mut *tx)
+            .await?;
+        let created = fetch_league(&mut tx, result.last_insert_rowid()).await?;
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    pub async fn get_leagues(&self) -> Result<Vec<League>, AppError> {
+        Ok(sqlx::query_as("SELECT * FROM leagues ORDER BY created_at DESC, id DESC")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn register_league_player(&self, league_id: i64, user_id: i64) -> Result<LeagueTable, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let current = fetch_league(&mut tx, league_id).await?;
+        if current.status != "registration" {
+            return Err(AppError::RuleViolation("League has already started".to_string()));
+        }
+        let user: Option<(i64,)> = sqlx::query_as("SELECT id FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if user.is_none() {
+            return Err(AppError::invalid(UNKNOWN_PLAYER, format!("User {} does not exist", user_id)));
+        }
+
+        let result = sqlx::query("INSERT INTO league_entries (league_id, user_id) VALUES (?, ?) ON CONFLICT DO NOTHING")
+            .bind(league_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::validation("User is already registered".to_string()));
+        }
+        tx.commit().await?;
+        self.get_league(league_id).await
+    }
+
+    // Draws every fixture up front; they can then be played in any order
+    pub async fn start_league(&self, league_id: i64) -> Result<LeagueTable, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let current = fetch_league(&mut tx, league_id).await?;
+        if current.status != "registration" {
+            return Err(AppError::RuleViolation("League has already started".to_string()));
+        }
+        let players: Vec<i64> = sqlx::query_scalar("SELECT user_id FROM league_entries WHERE league_id = ? ORDER BY id")
+            .bind(league_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        if players.len() < 2 {
+            return Err(AppError::validation("A league needs at least 2 players".to_string()));
+        }
+
+        for pairing in league::fixtures(players.len(), current.double_round) {
+            sqlx::query("INSERT INTO league_fixtures (league_id, round, position, player1_id, player2_id) VALUES (?, ?, ?, ?, ?)")
+                .bind(league_id)
+                .bind(pairing.round)
+                .bind(pairing.position)
+                .bind(players[pairing.player1])
+                .bind(players[pairing.player2])
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query("UPDATE leagues SET status = 'in_progress' WHERE id = ?")
+            .bind(league_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        self.get_league(league_id).await
+    }
+
+    // Standings are computed from the finished linked matches on every read
+    pub async fn get_league(&self, league_id: i64) -> Result<LeagueTable, AppError> {
+        let mut conn = self.pool.acquire().await?;
+        let current = fetch_league(&mut conn, league_id).await?;
+        let entries: Vec<LeagueEntry> = sqlx::query_as("SELECT * FROM league_entries WHERE league_id = ? ORDER BY id")
+            .bind(league_id)
+            .fetch_all(&mut *conn)
+            .await?;
+        let fixtures: Vec<LeagueFixture> = sqlx::query_as(&format!("{} WHERE f.league_id = ? ORDER BY f.round, f.position", LEAGUE_FIXTURES_SQL))
+            .bind(league_id)
+            .fetch_all(&mut *conn)
+            .await?;
+        if current.status == "registration" {
+            return Ok(LeagueTable { league: current, entries, fixtures, standings: Vec::new() });
+        }
+
+        let mut results = Vec::new();
+        for fixture in fixtures.iter().filter(|f| f.status == "finished") {
+            let m = fetch_match(&mut conn, fixture.match_id.unwrap_or_default()).await?;
+            let Some(winner) = m.winner_id else { continue };
+            let (points1, points2) = stats::points_won(&m);
+            results.push(league::LeagueResult { player1: m.player1_id, player2: m.player2_id, winner, points1, points2 });
+        }
+        let players: Vec<i64> = entries.iter().map(|e| e.user_id).collect();
+        let tie_breakers: Vec<String> = serde_json::from_str(&current.tie_breakers)?;
+        let standings = league::standings(&players, &results, &tie_breakers);
+        Ok(LeagueTable { league: current, entries, fixtures, standings })
+    }
+
+    // Starts a pending fixture through the same path as start_match. A fixture whose match
+    // was cancelled can be started again.
+    pub async fn start_league_fixture(&self, fixture_id: i64) -> Result<PopulatedMatch, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let fixture: LeagueFixture = sqlx::query_as(&format!("{} WHERE f.id = ?", LEAGUE_FIXTURES_SQL))
+            .bind(fixture_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound(format!("League fixture {} not found", fixture_id)))?;
+        let current = fetch_league(&mut tx, fixture.league_id).await?;
+        if current.status != "in_progress" {
+            return Err(AppError::RuleViolation("League is not in progress".to_string()));
+        }
+        if fixture.status != "pending" {
+            return Err(AppError::RuleViolation(format!("Fixture {} has already been started", fixture_id)));
+        }
+
+        let match_data = insert_match(&mut tx, StartMatchDto {
+            player1_id: fixture.player1_id,
+            player2_id: fixture.player2_id,
+            game_mode_id: current.game_mode_id,
+            ..Default::default()
+        }).await?;
+        sqlx::query("UPDATE league_fixtures SET match_id = ? WHERE id = ?")
+            .bind(match_data.id)
+            .bind(fixture_id)
+            .execute(&mut *tx)
+            .await?;
+        let populated = populate_match_in(&mut tx, match_data).await?;
+        tx.commit().await?;
+        self.live.publish(&populated);
+        Ok(populated)
+    }
+
+    // --- Statistics ---
+
+    async fn finished_matches_of(&self, user_id: i64) -> Result<Vec<Match>, AppError> {
+        Ok(sqlx::query_as(
+            "SELECT * FROM matches WHERE (player1_id = ? OR player2_id = ? OR player3_id = ? OR player4_id = ?) AND status = 'finished' ORDER BY start_time ASC"
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn get_user_statistics(&self, user_id: i64) -> Result<UserStatistics, AppError> {
+        let _user: User = sqlx::query_as("SELECT * FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let matches = self.finished_matches_of(user_id).await?;
+
+        let users: Vec<User> = sqlx::query_as("SELECT * FROM users").fetch_all(&self.pool).await?;
+        let user_map: HashMap<i64, String> = users.into_iter().map(|u| (u.id, u.name)).collect();
+
+        let modes: Vec<GameMode> = sqlx::query_as("SELECT * FROM game_modes").fetch_all(&self.pool).await?;
+        let mode_map_name: HashMap<i64, String> = modes.into_iter().map(|m| (m.id, m.name)).collect();
+
+        Ok(stats::user_statistics(user_id, &matches, &user_map, &mode_map_name))
+    }
+
+    // Replays every match from its event log, then recounts each user's wins and matches played
+    // from the finished results, so counters that drifted (e.g. after manual SQL edits) are fixed
+    pub async fn recompute_statistics(&self) -> Result<RecomputeSummary, AppError> {
+        let mut tx = begin_write(&self.pool).await?;
+        let matches: Vec<Match> = sqlx::query_as("SELECT * FROM matches ORDER BY id")
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let mut summary = RecomputeSummary { matches_rebuilt: 0, users_updated: 0, results_changed: 0 };
+        let mut rebuilt = Vec::with_capacity(matches.len());
+        // Matches whose stored state was rewritten; the others keep their version, so a window
+        // scoring one of them doesn't get a conflict
+        let mut changed = Vec::new();
+        for match_data in matches {
+            let old_winner = match_data.winner_id;
+            let events: Vec<MatchEvent> = serde_json::from_str(&match_data.events)?;
+            summary.matches_rebuilt += 1;
+            if !replay_changes(&match_data, &events)? {
+                rebuilt.push(match_data);
+                continue;
+            }
+            let match_data = apply_events(&mut tx, match_data, events).await?;
+            if match_data.winner_id != old_winner {
+                summary.results_changed += 1;
+            }
+            changed.push(match_data.id);
+            rebuilt.push(match_data);
+        }
+
+        summary.users_updated = sqlx::query("UPDATE users SET wins = 0, matches_played = 0")
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+        for m in rebuilt.iter().filter(|m| m.status == "finished") {
+            if let Some(side) = m.winner_id.and_then(|wid| side_of(m, wid)) {
+                apply_result_counters(&mut tx, m, side, 1).await?;
+            }
+        }
+        // Brackets and league tables follow the corrected results
+        for &match_id in &changed {
+            sync_competitions(&mut tx, match_id).await?;
+        }
+
+        tx.commit().await?;
+        let changed: Vec<Match> = rebuilt.into_iter().filter(|m| changed.contains(&m.id)).collect();
+        for m in self.populate_all(changed).await? {
+            self.live.publish(&m);
+        }
+        Ok(summary)
+    }
+
+    // Starting scores that would even out the two players, from their points won in finished matches
+    pub async fn suggest_handicap(&self, player1_id: i64, player2_id: i64, game_mode_id: i64) -> Result<ScoreSnapshot, AppError> {
+        let gm: GameMode = sqlx::query_as("SELECT * FROM game_modes WHERE id = ?")
+            .bind(game_mode_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(AppError::NotFound(format!("GameMode {} not found", game_mode_id)))?;
+
+        let mut shares = Vec::new();
+        for user_id in [player1_id, player2_id] {
+            let matches = self.finished_matches_of(user_id).await?;
+            let (scored, conceded) = stats::points_for_user(user_id, &matches);
+            shares.push(handicap::points_share(scored, conceded));
+        }
+
+        Ok(handicap::suggest_handicap(shares[0], shares[1], gm.points_to_win))
+    }
+
+    // --- Key Bindings ---
+
+    pub async fn get_key_bindings(&self) -> Result<Vec<KeyBinding>, AppError> {
+        Ok(sqlx::query_as::<_, KeyBinding>("SELECT * FROM key_bindings ORDER BY action, key_code")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn set_key_binding(&self, action: &str, key_code: &str, label: &str) -> Result<KeyBinding, AppError> {
+        sqlx::query(
+            "INSERT INTO key_bindings (action, key_code, label, is_default) VALUES (?, ?, ?, 0)
+             ON CONFLICT(action, key_code) DO UPDATE SET label = excluded.label, is_default = 0"
+        )
+        .bind(action)
+        .bind(key_code)
+        .bind(label)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(sqlx::query_as::<_, KeyBinding>("SELECT * FROM key_bindings WHERE action = ? AND key_code = ?")
+            .bind(action)
+            .bind(key_code)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    pub async fn delete_key_binding(&self, id: i64) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM key_bindings WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn reset_key_bindings(&self) -> Result<Vec<KeyBinding>, AppError> {
+        sqlx::query("DELETE FROM key_bindings")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(DEFAULT_KEY_BINDINGS_SQL)
+            .execute(&self.pool)
+            .await?;
+
+        self.get_key_bindings().await
+    }
+
+    // --- Settings ---
+
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>, AppError> {
+        Ok(sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    pub async fn set_setting(&self, key: &str, value: &str) -> Result<(), AppError> {
+        sqlx::query("INSERT INTO settings (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value")
+            .bind(key)
+            .bind(value)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Singles match between users 1 and 2, to 11 points, user 1 serving first
+    async fn seed_match() -> (Repository, i64) {
+        static NEXT_DB: AtomicUsize = AtomicUsize::new(0);
+        let pool = db::open_in_memory(&format!("pong-core-unit-{}", NEXT_DB.fetch_add(1, Ordering::SeqCst))).await.unwrap();
+        let repo = Repository::new(pool);
+        sqlx::query("INSERT INTO users (name) VALUES ('Alice'), ('Bob')").execute(repo.pool()).await.unwrap();
+        repo.ensure_basic_game_mode().await.unwrap();
+        let m = repo.start_match(StartMatchDto { player1_id: 1, player2_id: 2, game_mode_id: 1, ..Default::default() }, None).await.unwrap();
+        repo.set_first_server(m.id, 1, None).await.unwrap();
+        (repo, m.id)
+    }
+
+    async fn load_match(repo: &Repository, id: i64) -> Match {
+        let mut conn = repo.pool().acquire().await.unwrap();
+        fetch_match(&mut conn, id).await.unwrap()
+    }
+
+    async fn user_counters(repo: &Repository, id: i64) -> (i64, i64) {
+        sqlx::query_as("SELECT wins, matches_played FROM users WHERE id = ?")
+            .bind(id)
+            .fetch_one(repo.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn stale_version_is_a_conflict() {
+        let (repo, match_id) = seed_match().await;
+        let stale = load_match(&repo, match_id).await;
+
+        repo.add_point(match_id, 1, None, None, None).await.unwrap();
+
+        let mut tx = begin_write(repo.pool()).await.unwrap();
+        let result = append_event(&mut tx, stale, MatchEventType::Point, Some(2)).await;
+        assert_eq!(result.unwrap_err(), AppError::conflict());
+        drop(tx);
+
+        let m = load_match(&repo, match_id).await;
+        assert_eq!((m.score_p1, m.score_p2), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn failed_write_rolls_back_counters() {
+        let (repo, match_id) = seed_match().await;
+        for _ in 0..10 {
+            repo.add_point(match_id, 1, None, None, None).await.unwrap();
+        }
+        let stale = load_match(&repo, match_id).await;
+        repo.add_point(match_id, 2, None, None, None).await.unwrap();
+
+        // The game point is replayed on a stale copy: counters are updated, then the match write conflicts
+        let mut tx = begin_write(repo.pool()).await.unwrap();
+        let result = append_event(&mut tx, stale, MatchEventType::Point, Some(1)).await;
+        assert_eq!(result.unwrap_err(), AppError::conflict());
+        tx.rollback().await.unwrap();
+
+        assert_eq!(user_counters(&repo, 1).await, (0, 0));
+        assert_eq!(load_match(&repo, match_id).await.status, "in_progress");
+    }
+}
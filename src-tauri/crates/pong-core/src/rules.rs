@@ -0,0 +1,146 @@
+// Checks applied before anything is written: game mode settings, lineups, handicaps and
+// the per-match limits (timeouts, pauses). None of them touch the database.
+
+use crate::error::AppError;
+use crate::models::{GameMode, Match, MatchEvent, MatchEventType, MatchRules, PauseInterval, MATCH_RULES_VERSION};
+use crate::replay::side_of;
+use crate::scoring;
+
+// start_match rejections: validation errors whose message reads "CODE: details"
+pub const SAME_PLAYER_BOTH_SIDES: &str = "SAME_PLAYER_BOTH_SIDES";
+pub const DUPLICATE_PARTNER: &str = "DUPLICATE_PARTNER";
+pub const INCOMPLETE_DOUBLES: &str = "INCOMPLETE_DOUBLES";
+pub const UNKNOWN_PLAYER: &str = "UNKNOWN_PLAYER";
+pub const UNKNOWN_GAME_MODE: &str = "UNKNOWN_GAME_MODE";
+pub const ARCHIVED_GAME_MODE: &str = "ARCHIVED_GAME_MODE";
+
+pub const TIMEOUTS_PER_SIDE: i64 = 1;
+
+pub fn snapshot_rules(gm: &GameMode) -> MatchRules {
+    MatchRules {
+        version: MATCH_RULES_VERSION,
+        points_to_win: gm.points_to_win,
+        serves_before_change: gm.serves_before_change,
+        is_deuce_enabled: gm.is_deuce_enabled,
+        serves_in_deuce: gm.serves_in_deuce,
+        serve_type: gm.serve_type.clone().unwrap_or("free".to_string()),
+        games_to_win: gm.games_to_win,
+        scoring_type: gm.scoring_type.clone(),
+        points_cap: gm.points_cap,
+        first_server_id: None,
+        handicap_p1: 0,
+        handicap_p2: 0,
+    }
+}
+
+pub fn validate_game_mode(
+    points_to_win: i64,
+    serves_before_change: i64,
+    serves_in_deuce: i64,
+    games_to_win: i64,
+    scoring_type: &str,
+    points_cap: Option<i64>,
+) -> Result<(), AppError> {
+    if points_to_win < 1 || serves_before_change < 1 || serves_in_deuce < 1 {
+        return Err(AppError::validation("Points to win and serves must be at least 1".to_string()));
+    }
+    if games_to_win < 1 {
+        return Err(AppError::validation("Games to win must be at least 1".to_string()));
+    }
+    if !scoring::SCORING_TYPES.contains(&scoring_type) {
+        return Err(AppError::validation(format!("Unknown scoring type: {}", scoring_type)));
+    }
+    if scoring_type == scoring::CAPPED && points_cap.map_or(true, |cap| cap <= points_to_win) {
+        return Err(AppError::validation("Capped scoring needs a points cap above points to win".to_string()));
+    }
+    Ok(())
+}
+
+// Shape of the teams: no one on both sides or twice on one side, and doubles need both partners
+pub fn check_lineup(player1_id: i64, player2_id: i64, player3_id: Option<i64>, player4_id: Option<i64>) -> Result<(), AppError> {
+    if player3_id.is_some() != player4_id.is_some() {
+        return Err(AppError::invalid(INCOMPLETE_DOUBLES, "Doubles need a partner on both sides"));
+    }
+
+    let side1: Vec<i64> = std::iter::once(player1_id).chain(player3_id).collect();
+    let side2: Vec<i64> = std::iter::once(player2_id).chain(player4_id).collect();
+    if let Some(id) = side1.iter().find(|id| side2.contains(id)) {
+        return Err(AppError::invalid(SAME_PLAYER_BOTH_SIDES, format!("Player {} is on both sides", id)));
+    }
+    for side in [&side1, &side2] {
+        if side.len() == 2 && side[0] == side[1] {
+            return Err(AppError::invalid(DUPLICATE_PARTNER, format!("Player {} is their own partner", side[0])));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_handicap(match_rules: &MatchRules) -> Result<(), AppError> {
+    for handicap in [match_rules.handicap_p1, match_rules.handicap_p2] {
+        if handicap < 0 || handicap >= match_rules.points_to_win {
+            return Err(AppError::validation("Handicap must be between 0 and points to win".to_string()));
+        }
+    }
+    Ok(())
+}
+
+pub fn count_timeouts(m: &Match, events: &[MatchEvent], side: &str) -> i64 {
+    events.iter()
+        .filter(|e| e.event_type == MatchEventType::Timeout)
+        .filter(|e| e.element_id.as_deref().and_then(|id| id.parse::<i64>().ok()).and_then(|id| side_of(m, id)) == Some(side))
+        .count() as i64
+}
+
+pub fn is_paused(pauses: &[PauseInterval]) -> bool {
+    pauses.last().is_some_and(|p| p.ended_at.is_none())
+}
+
+// Scoring, lets, timeouts, pauses and forfeits only happen during play
+pub fn ensure_in_progress(m: &Match) -> Result<(), AppError> {
+    match m.status.as_str() {
+        "in_progress" => Ok(()),
+        "abandoned" => Err(AppError::RuleViolation("Match is abandoned".to_string())),
+        _ => Err(AppError::RuleViolation("Match is finished".to_string())),
+    }
+}
+
+// Undo and redo can reopen a finished match, but an abandoned one stays as it was left
+pub fn ensure_not_abandoned(m: &Match) -> Result<(), AppError> {
+    if m.status == "abandoned" {
+        return Err(AppError::RuleViolation("Match is abandoned".to_string()));
+    }
+    Ok(())
+}
+
+pub fn ensure_not_paused(m: &Match) -> Result<(), AppError> {
+    let pauses: Vec<PauseInterval> = serde_json::from_str(&m.pauses).unwrap_or_default();
+    if is_paused(&pauses) {
+        return Err(AppError::RuleViolation("Match is paused".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code_of(result: Result<(), AppError>) -> &'static str {
+        match result.unwrap_err() {
+            AppError::Validation { reason: Some(reason), .. } => reason,
+            other => panic!("expected a validation reason, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn lineups_are_checked() {
+        assert!(check_lineup(1, 2, None, None).is_ok());
+        assert!(check_lineup(1, 2, Some(3), Some(4)).is_ok());
+        assert_eq!(code_of(check_lineup(1, 1, None, None)), SAME_PLAYER_BOTH_SIDES);
+        assert_eq!(code_of(check_lineup(1, 2, Some(2), Some(3))), SAME_PLAYER_BOTH_SIDES);
+        assert_eq!(code_of(check_lineup(1, 2, Some(3), Some(3))), SAME_PLAYER_BOTH_SIDES);
+        assert_eq!(code_of(check_lineup(1, 2, Some(1), Some(3))), DUPLICATE_PARTNER);
+        assert_eq!(code_of(check_lineup(1, 2, Some(3), Some(2))), DUPLICATE_PARTNER);
+        assert_eq!(code_of(check_lineup(1, 2, Some(3), None)), INCOMPLETE_DOUBLES);
+        assert_eq!(code_of(check_lineup(1, 2, None, Some(4))), INCOMPLETE_DOUBLES);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::models::MatchRules;
+
+pub const CLASSIC: &str = "classic";
+pub const CAPPED: &str = "capped";
+pub const GOLDEN_POINT: &str = "golden_point";
+pub const FIRST_TO: &str = "first_to";
+
+pub const SCORING_TYPES: [&str; 4] = [CLASSIC, CAPPED, GOLDEN_POINT, FIRST_TO];
+
+// Decides when a single game is over.
+pub trait ScoringRules: Send + Sync {
+    // Returns 'p1' or 'p2' once the game has been won
+    fn game_winner(&self, p1_score: i64, p2_score: i64) -> Option<String>;
+
+    // Deuce switches the serve rotation to `serves_in_deuce`
+    fn is_deuce(&self, p1_score: i64, p2_score: i64) -> bool;
+}
+
+fn leader(p1_score: i64, p2_score: i64) -> Option<String> {
+    if p1_score > p2_score {
+        Some("p1".to_string())
+    } else if p2_score > p1_score {
+        Some("p2".to_string())
+    } else {
+        None
+    }
+}
+
+fn both_reached(p1_score: i64, p2_score: i64, points: i64) -> bool {
+    p1_score >= points && p2_score >= points
+}
+
+// Classic table tennis: first to N, but a game must be won by 2 points.
+pub struct ClassicRules {
+    pub points_to_win: i64,
+}
+
+impl ScoringRules for ClassicRules {
+    fn game_winner(&self, p1_score: i64, p2_score: i64) -> Option<String> {
+        if p1_score.max(p2_score) >= self.points_to_win && (p1_score - p2_score).abs() >= 2 {
+            leader(p1_score, p2_score)
+        } else {
+            None
+        }
+    }
+
+    fn is_deuce(&self, p1_score: i64, p2_score: i64) -> bool {
+        both_reached(p1_score, p2_score, self.points_to_win - 1)
+    }
+}
+
+// Win by 2, but whoever reaches the cap first wins outright (e.g. 11, capped at 15).
+pub struct CappedRules {
+    pub points_to_win: i64,
+    pub points_cap: i64,
+}
+
+impl ScoringRules for CappedRules {
+    fn game_winner(&self, p1_score: i64, p2_score: i64) -> Option<String> {
+        if p1_score.max(p2_score) >= self.points_cap {
+            return leader(p1_score, p2_score);
+        }
+        ClassicRules { points_to_win: self.points_to_win }.game_winner(p1_score, p2_score)
+    }
+
+    fn is_deuce(&self, p1_score: i64, p2_score: i64) -> bool {
+        both_reached(p1_score, p2_score, self.points_to_win - 1)
+    }
+}
+
+// Deuce is reached as usual, but a single golden point decides it.
+pub struct GoldenPointRules {
+    pub points_to_win: i64,
+}
+
+impl ScoringRules for GoldenPointRules {
+    fn game_winner(&self, p1_score: i64, p2_score: i64) -> Option<String> {
+        if p1_score.max(p2_score) >= self.points_to_win {
+            leader(p1_score, p2_score)
+        } else {
+            None
+        }
+    }
+
+    fn is_deuce(&self, p1_score: i64, p2_score: i64) -> bool {
+        p1_score == self.points_to_win - 1 && p2_score == self.points_to_win - 1
+    }
+}
+
+// First to N points, no deuce at all: serves keep their normal rotation.
+pub struct FirstToRules {
+    pub points_to_win: i64,
+}
+
+impl ScoringRules for FirstToRules {
+    fn game_winner(&self, p1_score: i64, p2_score: i64) -> Option<String> {
+        if p1_score.max(p2_score) >= self.points_to_win {
+            leader(p1_score, p2_score)
+        } else {
+            None
+        }
+    }
+
+    fn is_deuce(&self, _p1_score: i64, _p2_score: i64) -> bool {
+        false
+    }
+}
+
+pub fn resolve(
+    scoring_type: &str,
+    points_to_win: i64,
+    is_deuce_enabled: bool,
+    points_cap: Option<i64>,
+) -> Box<dyn ScoringRules> {
+    match (scoring_type, points_cap) {
+        (CAPPED, Some(points_cap)) => Box::new(CappedRules { points_to_win, points_cap }),
+        (GOLDEN_POINT, _) => Box::new(GoldenPointRules { points_to_win }),
+        (FIRST_TO, _) => Box::new(FirstToRules { points_to_win }),
+        // Classic with deuce turned off plays straight to N
+        _ if !is_deuce_enabled => Box::new(FirstToRules { points_to_win }),
+        _ => Box::new(ClassicRules { points_to_win }),
+    }
+}
+
+pub fn rules_for_match(match_rules: &MatchRules) -> Box<dyn ScoringRules> {
+    resolve(
+        &match_rules.scoring_type,
+        match_rules.points_to_win,
+        match_rules.is_deuce_enabled,
+        match_rules.points_cap,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_requires_two_point_lead() {
+        let rules = ClassicRules { points_to_win: 11 };
+        assert_eq!(rules.game_winner(11, 9).as_deref(), Some("p1"));
+        assert_eq!(rules.game_winner(5, 11).as_deref(), Some("p2"));
+        assert_eq!(rules.game_winner(11, 10).as_deref(), None);
+        assert_eq!(rules.game_winner(12, 10).as_deref(), Some("p1"));
+        assert_eq!(rules.game_winner(17, 18).as_deref(), None);
+        assert!(rules.is_deuce(10, 10));
+        assert!(rules.is_deuce(13, 12));
+        assert!(!rules.is_deuce(10, 9));
+    }
+
+    #[test]
+    fn capped_stops_at_the_cap() {
+        let rules = CappedRules { points_to_win: 11, points_cap: 15 };
+        assert_eq!(rules.game_winner(11, 8).as_deref(), Some("p1"));
+        assert_eq!(rules.game_winner(13, 12).as_deref(), None);
+        assert_eq!(rules.game_winner(15, 14).as_deref(), Some("p1"));
+        assert_eq!(rules.game_winner(14, 15).as_deref(), Some("p2"));
+        assert!(rules.is_deuce(14, 14));
+    }
+
+    #[test]
+    fn golden_point_decides_deuce() {
+        let rules = GoldenPointRules { points_to_win: 11 };
+        assert_eq!(rules.game_winner(11, 7).as_deref(), Some("p1"));
+        assert_eq!(rules.game_winner(10, 10).as_deref(), None);
+        assert_eq!(rules.game_winner(10, 11).as_deref(), Some("p2"));
+        assert!(rules.is_deuce(10, 10));
+        assert!(!rules.is_deuce(10, 9));
+    }
+
+    #[test]
+    fn first_to_has_no_deuce() {
+        let rules = FirstToRules { points_to_win: 21 };
+        assert_eq!(rules.game_winner(21, 20).as_deref(), Some("p1"));
+        assert_eq!(rules.game_winner(20, 20).as_deref(), None);
+        assert_eq!(rules.game_winner(3, 21).as_deref(), Some("p2"));
+        assert!(!rules.is_deuce(20, 20));
+    }
+
+    #[test]
+    fn resolve_falls_back_to_deuce_flag() {
+        let no_deuce = resolve(CLASSIC, 11, false, None);
+        assert_eq!(no_deuce.game_winner(11, 10).as_deref(), Some("p1"));
+
+        let classic = resolve(CLASSIC, 11, true, None);
+        assert_eq!(classic.game_winner(11, 10).as_deref(), None);
+
+        // A capped mode without a cap behaves like classic
+        let uncapped = resolve(CAPPED, 11, true, None);
+        assert_eq!(uncapped.game_winner(15, 14).as_deref(), None);
+    }
+}
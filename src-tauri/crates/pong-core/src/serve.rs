@@ -0,0 +1,222 @@
+// Serve rotation engine.
+// Mirrors the ITTF rules: service changes every `serves_before_change` points,
+// every `serves_in_deuce` points once the scoring rules report deuce,
+// and the side that received first in a game serves first in the next one.
+// In the last possible game of a doubles match the receiving pair changes order
+// once a pair reaches 5 points.
+
+pub struct ServeInput {
+    pub player1_id: i64,
+    pub player2_id: i64,
+    pub player3_id: Option<i64>, // Doubles partner for P1
+    pub player4_id: Option<i64>, // Doubles partner for P2
+    pub first_server_id: Option<i64>,
+    pub score_p1: i64,
+    pub score_p2: i64,
+    pub handicap_points: i64, // Part of the score given at the start of the game, not played
+    pub games_played: i64,
+    pub points_to_win: i64,
+    pub serves_before_change: i64,
+    pub serves_in_deuce: i64,
+    pub is_deuce: bool, // Decided by the match's scoring rules
+    pub decider_switch_at: Option<i64>, // Rallies played when a pair reached 5 in the last possible game
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServeState {
+    pub current_server_id: Option<i64>,
+    pub next_server_id: Option<i64>,
+    pub serves_remaining: i64,
+}
+
+impl ServeState {
+    fn unknown() -> Self {
+        ServeState {
+            current_server_id: None,
+            next_server_id: None,
+            serves_remaining: 0,
+        }
+    }
+}
+
+// Order in which players serve during the first game.
+// Singles: Starter -> Receiver.
+// Doubles: A -> X -> B -> Y, where A is the starter, X the opposing captain,
+// B the starter's partner and Y the opposing partner.
+fn first_game_rotation(input: &ServeInput, starter_id: i64) -> Option<Vec<i64>> {
+    let team1 = [Some(input.player1_id), input.player3_id];
+    let team2 = [Some(input.player2_id), input.player4_id];
+
+    match (input.player3_id, input.player4_id) {
+        (Some(p3), Some(p4)) => {
+            if team1.contains(&Some(starter_id)) {
+                let partner = if starter_id == input.player1_id { p3 } else { input.player1_id };
+                Some(vec![starter_id, input.player2_id, partner, p4])
+            } else if team2.contains(&Some(starter_id)) {
+                let partner = if starter_id == input.player2_id { p4 } else { input.player2_id };
+                Some(vec![starter_id, input.player1_id, partner, p3])
+            } else {
+                None
+            }
+        }
+        _ => {
+            if starter_id == input.player1_id {
+                Some(vec![input.player1_id, input.player2_id])
+            } else if starter_id == input.player2_id {
+                Some(vec![input.player2_id, input.player1_id])
+            } else {
+                None
+            }
+        }
+    }
+}
+
+// In every other game the first receiver serves first, to the player who served to them.
+// Singles: [S, R] -> [R, S]. Doubles: [A, X, B, Y] -> [X, A, Y, B].
+fn rotation_for_game(first_game: Vec<i64>, games_played: i64) -> Vec<i64> {
+    if games_played % 2 == 0 {
+        return first_game;
+    }
+    if first_game.len() == 4 {
+        vec![first_game[1], first_game[0], first_game[3], first_game[2]]
+    } else {
+        first_game.into_iter().rev().collect()
+    }
+}
+
+// A "turn" is one server's block of consecutive serves: (turn index, serves left in it)
+// once `rallies` rallies of the game have been played
+fn turn_at(input: &ServeInput, rallies: i64, is_deuce: bool) -> (i64, i64) {
+    let serves_before_change = input.serves_before_change.max(1);
+    let serves_in_deuce = input.serves_in_deuce.max(1);
+    let points_before_deuce = ((input.points_to_win - 1) * 2 - input.handicap_points).max(0);
+
+    if is_deuce {
+        // Deuce starts a fresh turn, continuing the same rotation order
+        let turns_before_deuce = (points_before_deuce + serves_before_change - 1) / serves_before_change;
+        let offset = rallies - points_before_deuce;
+        (
+            turns_before_deuce + offset / serves_in_deuce,
+            serves_in_deuce - offset % serves_in_deuce,
+        )
+    } else {
+        let mut remaining = serves_before_change - rallies % serves_before_change;
+        if rallies < points_before_deuce {
+            remaining = remaining.min(points_before_deuce - rallies);
+        }
+        (rallies / serves_before_change, remaining)
+    }
+}
+
+pub fn compute_serve_state(input: &ServeInput) -> ServeState {
+    let starter_id = match input.first_server_id {
+        Some(id) => id,
+        None => return ServeState::unknown(),
+    };
+    let rotation = match first_game_rotation(input, starter_id) {
+        Some(r) => rotation_for_game(r, input.games_played),
+        None => return ServeState::unknown(),
+    };
+
+    // Rotation follows rallies played, so a handicap start doesn't shift it
+    let total_points = input.score_p1 + input.score_p2 - input.handicap_points;
+    let (turn_index, serves_remaining) = turn_at(input, total_points, input.is_deuce);
+
+    // The pair receiving when the switch happened swaps places in the rotation;
+    // the server of that turn keeps serving, to the other receiver
+    let mut rotation = rotation;
+    if let (4, Some(switch_at)) = (rotation.len(), input.decider_switch_at) {
+        if total_points >= switch_at {
+            let server = (turn_at(input, switch_at, false).0 % 4) as usize;
+            rotation.swap((server + 1) % 4, (server + 3) % 4);
+        }
+    }
+
+    let len = rotation.len() as i64;
+    ServeState {
+        current_server_id: Some(rotation[(turn_index % len) as usize]),
+        next_server_id: Some(rotation[((turn_index + 1) % len) as usize]),
+        serves_remaining,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Singles to 11, two serves each, player 1 serving first
+    fn input(score_p1: i64, score_p2: i64) -> ServeInput {
+        ServeInput {
+            player1_id: 1,
+            player2_id: 2,
+            player3_id: None,
+            player4_id: None,
+            first_server_id: Some(1),
+            score_p1,
+            score_p2,
+            handicap_points: 0,
+            games_played: 0,
+            points_to_win: 11,
+            serves_before_change: 2,
+            serves_in_deuce: 1,
+            is_deuce: false,
+            decider_switch_at: None,
+        }
+    }
+
+    fn doubles(score_p1: i64, score_p2: i64) -> ServeInput {
+        ServeInput { player3_id: Some(3), player4_id: Some(4), ..input(score_p1, score_p2) }
+    }
+
+    fn server(input: &ServeInput) -> (Option<i64>, Option<i64>, i64) {
+        let state = compute_serve_state(input);
+        (state.current_server_id, state.next_server_id, state.serves_remaining)
+    }
+
+    #[test]
+    fn singles_change_every_two_serves_and_every_point_at_deuce() {
+        assert_eq!(server(&input(0, 0)), (Some(1), Some(2), 2));
+        assert_eq!(server(&input(1, 0)), (Some(1), Some(2), 1));
+        assert_eq!(server(&input(1, 1)), (Some(2), Some(1), 2));
+        assert_eq!(server(&input(3, 1)), (Some(1), Some(2), 2));
+        assert_eq!(server(&input(10, 10)), (Some(1), Some(2), 2));
+        assert_eq!(server(&ServeInput { is_deuce: true, ..input(10, 10) }), (Some(1), Some(2), 1));
+        assert_eq!(server(&ServeInput { is_deuce: true, ..input(11, 10) }), (Some(2), Some(1), 1));
+        assert_eq!(compute_serve_state(&ServeInput { first_server_id: None, ..input(0, 0) }), ServeState::unknown());
+        assert_eq!(compute_serve_state(&ServeInput { first_server_id: Some(9), ..input(0, 0) }), ServeState::unknown());
+    }
+
+    #[test]
+    fn later_games_start_with_the_first_receiver() {
+        assert_eq!(server(&ServeInput { games_played: 1, ..input(0, 0) }), (Some(2), Some(1), 2));
+        assert_eq!(server(&ServeInput { games_played: 2, ..input(0, 0) }), (Some(1), Some(2), 2));
+
+        // Doubles: A -> X -> B -> Y in the first game, X -> A -> Y -> B in the second
+        let turns = |games_played| -> Vec<Option<i64>> {
+            (0..4).map(|turn| compute_serve_state(&ServeInput { games_played, ..doubles(turn * 2, 0) }).current_server_id).collect()
+        };
+        assert_eq!(turns(0), vec![Some(1), Some(2), Some(3), Some(4)]);
+        assert_eq!(turns(1), vec![Some(2), Some(1), Some(4), Some(3)]);
+    }
+
+    #[test]
+    fn handicap_points_do_not_shift_the_rotation() {
+        let handicap = |score_p1, score_p2| ServeInput { handicap_points: 3, ..input(score_p1, score_p2) };
+        assert_eq!(server(&handicap(3, 0)), (Some(1), Some(2), 2));
+        assert_eq!(server(&handicap(4, 1)), (Some(2), Some(1), 2));
+    }
+
+    #[test]
+    fn receivers_change_order_at_five_in_the_last_doubles_game() {
+        // Without the switch player 3 would serve to player 4 at 5-0
+        assert_eq!(server(&doubles(4, 0)), (Some(3), Some(4), 2));
+        let switched = |score_p1, score_p2| ServeInput { decider_switch_at: Some(5), ..doubles(score_p1, score_p2) };
+        assert_eq!(server(&switched(4, 0)), (Some(3), Some(4), 2));
+        assert_eq!(server(&switched(5, 0)), (Some(3), Some(2), 1));
+        assert_eq!(server(&switched(5, 1)), (Some(2), Some(1), 2));
+        assert_eq!(server(&switched(5, 3)), (Some(1), Some(4), 2));
+
+        // Singles have no receiving order to change
+        assert_eq!(server(&ServeInput { decider_switch_at: Some(5), ..input(5, 0) }), server(&input(5, 0)));
+    }
+}
@@ -0,0 +1,265 @@
+// Figures derived from stored matches: points actually won, playing time and
+// the per-user statistics shown on the profile page.
+
+use crate::models::{
+    ForfeitReason, ForfeitStat, GameScore, Match, MatchEvent, MatchEventType, MatchRules, ModeStat, OpponentStat,
+    PauseInterval, PointReasonStat, RecentMatch, UserStatistics,
+};
+use crate::replay::side_of;
+use chrono::Utc;
+use std::collections::HashMap;
+
+pub fn count_games_won(games: &[GameScore]) -> (i64, i64) {
+    let p1 = games.iter().filter(|g| g.winner == "p1").count() as i64;
+    let p2 = games.iter().filter(|g| g.winner == "p2").count() as i64;
+    (p1, p2)
+}
+
+// Wall-clock time between start and end (or now), minus every pause
+pub fn active_duration_ms(m: &Match) -> i64 {
+    let pauses: Vec<PauseInterval> = serde_json::from_str(&m.pauses).unwrap_or_default();
+    let end = m.end_time.unwrap_or_else(Utc::now).timestamp_millis();
+    let paused: i64 = pauses.iter()
+        .map(|p| p.ended_at.unwrap_or(end).min(end) - p.started_at)
+        .sum();
+    (end - m.start_time.timestamp_millis() - paused).max(0)
+}
+
+// Points each side actually won over the whole match: handicap starts are left out,
+// and the game in progress when a match was forfeited is included
+pub fn points_won(m: &Match) -> (i64, i64) {
+    let games: Vec<GameScore> = serde_json::from_str(&m.games).unwrap_or_default();
+    let events: Vec<MatchEvent> = serde_json::from_str(&m.events).unwrap_or_default();
+    let (mut total_p1, mut total_p2) = if games.is_empty() {
+        (m.score_p1, m.score_p2)
+    } else {
+        (games.iter().map(|g| g.p1).sum(), games.iter().map(|g| g.p2).sum())
+    };
+
+    let partial_game = forfeit_reason(&events).is_some() && !games.is_empty();
+    if partial_game {
+        total_p1 += m.score_p1;
+        total_p2 += m.score_p2;
+    }
+    if let Ok(rules) = serde_json::from_str::<MatchRules>(&m.match_rules) {
+        let games_started = (games.len() as i64).max(1) + partial_game as i64;
+        total_p1 -= rules.handicap_p1.max(0) * games_started;
+        total_p2 -= rules.handicap_p2.max(0) * games_started;
+    }
+    (total_p1, total_p2)
+}
+
+// Reason of the forfeit that ended the match, if it didn't end on points
+pub fn forfeit_reason(events: &[MatchEvent]) -> Option<ForfeitReason> {
+    events.iter()
+        .rev()
+        .find(|e| e.event_type == MatchEventType::Forfeit)
+        .and_then(|e| e.forfeit_reason)
+}
+
+// Points `user_id`'s side scored and conceded across `matches`, for handicap suggestions
+pub fn points_for_user(user_id: i64, matches: &[Match]) -> (i64, i64) {
+    let (mut scored, mut conceded) = (0, 0);
+    for m in matches {
+        let (p1, p2) = points_won(m);
+        if side_of(m, user_id) == Some("p1") {
+            scored += p1;
+            conceded += p2;
+        } else {
+            scored += p2;
+            conceded += p1;
+        }
+    }
+    (scored, conceded)
+}
+
+// `matches` are the user's finished matches, oldest first
+pub fn user_statistics(
+    user_id: i64,
+    matches: &[Match],
+    user_map: &HashMap<i64, String>,
+    mode_map_name: &HashMap<i64, String>,
+) -> UserStatistics {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut current_streak = 0;
+    let mut best_streak = 0;
+    let mut pts_scored = 0;
+    let mut pts_conceded = 0;
+    let mut aces = 0;
+    let mut errors = 0;
+    let mut points_played = 0;
+    let mut active_time_ms = 0;
+    // reason -> points won that way
+    let mut won_by_map: HashMap<MatchEventType, i64> = HashMap::new();
+    // reason -> (wins, losses) of matches ended by forfeit
+    let mut forfeit_map: HashMap<ForfeitReason, (i64, i64)> = HashMap::new();
+    
+    // mode_name -> (wins, losses)
+    let mut mode_stats_map: HashMap<String, (i64, i64)> = HashMap::new(); 
+    // opponent_id -> (wins_against, losses_against)
+    let mut opponent_stats_map: HashMap<i64, (i64, i64)> = HashMap::new(); 
+    
+    let mut recent_matches = Vec::new();
+    
+    for m in matches {
+        let is_p1_side = m.player1_id == user_id || m.player3_id == Some(user_id);
+        
+        // Points are summed over every game; multi-game matches are reported in games won
+        let games: Vec<GameScore> = serde_json::from_str(&m.games).unwrap_or_default();
+        let events: Vec<MatchEvent> = serde_json::from_str(&m.events).unwrap_or_default();
+        let forfeit = forfeit_reason(&events);
+        let (total_p1, total_p2) = points_won(m);
+        let (result_p1, result_p2) = if games.len() > 1 {
+            count_games_won(&games)
+        } else {
+            (m.score_p1, m.score_p2)
+        };
+
+        let p_score = if is_p1_side { total_p1 } else { total_p2 };
+        let opp_score = if is_p1_side { total_p2 } else { total_p1 };
+        let result_user = if is_p1_side { result_p1 } else { result_p2 };
+        let result_opponent = if is_p1_side { result_p2 } else { result_p1 };
+        
+        let opponent_id = if is_p1_side { m.player2_id } else { m.player1_id }; // Default to Captain
+        let opponent_name = user_map.get(&opponent_id).cloned().unwrap_or("Unknown".to_string());
+        
+        // Use captain check for winner determination
+        let is_win = if is_p1_side { m.winner_id == Some(m.player1_id) } else { m.winner_id == Some(m.player2_id) }; 
+        
+        let mode_name = mode_map_name.get(&m.game_mode_id).cloned().unwrap_or("Unknown".to_string());
+        
+        pts_scored += p_score;
+        pts_conceded += opp_score;
+        active_time_ms += active_duration_ms(m);
+
+        // How points were won goes to the player credited with the point, so partners don't share
+        // aces; errors only name the side that won the point, so they count against the team
+        let user_side = if is_p1_side { "p1" } else { "p2" };
+        for e in events.iter().filter(|e| e.event_type.is_point()) {
+            let credited = e.element_id.as_deref().and_then(|id| id.parse::<i64>().ok());
+            let Some(credited_side) = credited.and_then(|id| side_of(m, id)) else { continue };
+
+            points_played += 1;
+            if credited == Some(user_id) {
+                *won_by_map.entry(e.event_type).or_insert(0) += 1;
+                if e.event_type == MatchEventType::Ace {
+                    aces += 1;
+                }
+            } else if credited_side != user_side && matches!(e.event_type, MatchEventType::ServiceFault | MatchEventType::UnforcedError) {
+                errors += 1;
+            }
+        }
+        
+        if let Some(reason) = forfeit {
+            let entry = forfeit_map.entry(reason).or_insert((0, 0));
+            if is_win { entry.0 += 1; } else { entry.1 += 1; }
+        }
+
+        if is_win {
+            wins += 1;
+            current_streak += 1;
+            if current_streak > best_streak {
+                best_streak = current_streak;
+            }
+            
+            let entry = mode_stats_map.entry(mode_name.clone()).or_insert((0, 0));
+            entry.0 += 1;
+            
+            let entry = opponent_stats_map.entry(opponent_id).or_insert((0, 0));
+            entry.0 += 1;
+        } else {
+            losses += 1;
+            current_streak = 0;
+            
+            let entry = mode_stats_map.entry(mode_name.clone()).or_insert((0, 0));
+            entry.1 += 1;
+            
+            let entry = opponent_stats_map.entry(opponent_id).or_insert((0, 0));
+            entry.1 += 1;
+        }
+        
+        recent_matches.push(RecentMatch {
+            match_id: m.id,
+            date: m.start_time.to_rfc3339(),
+            opponent_name,
+            result: if is_win { "Win".to_string() } else { "Loss".to_string() },
+            score_user: result_user,
+            score_opponent: result_opponent,
+            mode_name,
+            forfeit_reason: forfeit,
+        });
+    }
+    
+    recent_matches.reverse();
+    recent_matches.truncate(10);
+    
+    let nemesis = opponent_stats_map.iter()
+        .max_by_key(|(_, stats)| stats.1) // max losses
+        .map(|(oid, stats)| OpponentStat {
+            opponent_id: *oid,
+            opponent_name: user_map.get(oid).cloned().unwrap_or("Unknown".to_string()),
+            count: stats.1
+        });
+        
+    let victim = opponent_stats_map.iter()
+        .max_by_key(|(_, stats)| stats.0) // max wins
+        .map(|(oid, stats)| OpponentStat {
+            opponent_id: *oid,
+            opponent_name: user_map.get(oid).cloned().unwrap_or("Unknown".to_string()),
+            count: stats.0
+        });
+
+    let mode_stats: Vec<ModeStat> = mode_stats_map.into_iter().map(|(name, (w, l))| {
+        let total = w + l;
+        let rate = if total > 0 { w as f64 / total as f64 } else { 0.0 };
+        ModeStat {
+            mode_name: name,
+            wins: w,
+            losses: l,
+            win_rate: rate,
+        }
+    }).collect();
+    
+    let total_played = wins + losses;
+    let win_rate = if total_played > 0 { wins as f64 / total_played as f64 } else { 0.0 };
+    let average_match_duration_ms = if total_played > 0 { active_time_ms / total_played } else { 0 };
+    let aces_per_match = if total_played > 0 { aces as f64 / total_played as f64 } else { 0.0 };
+    let error_rate = if points_played > 0 { errors as f64 / points_played as f64 } else { 0.0 };
+
+    let mut points_won_by: Vec<PointReasonStat> = won_by_map.into_iter()
+        .map(|(reason, count)| PointReasonStat { reason, count })
+        .collect();
+    points_won_by.sort_by_key(|s| std::cmp::Reverse(s.count));
+
+    let forfeit_wins = forfeit_map.values().map(|(w, _)| w).sum();
+    let forfeit_losses = forfeit_map.values().map(|(_, l)| l).sum();
+    let forfeits: Vec<ForfeitStat> = forfeit_map.into_iter()
+        .map(|(reason, (wins, losses))| ForfeitStat { reason, wins, losses })
+        .collect();
+
+    UserStatistics {
+        user_id,
+        wins,
+        losses,
+        matches_played: total_played,
+        win_rate,
+        current_streak,
+        best_streak,
+        points_scored: pts_scored,
+        points_conceded: pts_conceded,
+        aces,
+        aces_per_match,
+        errors,
+        error_rate,
+        average_match_duration_ms,
+        forfeit_wins,
+        forfeit_losses,
+        forfeits,
+        points_won_by,
+        mode_stats,
+        recent_matches,
+        nemesis,
+        victim,
+    }
+}
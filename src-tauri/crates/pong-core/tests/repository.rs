@@ -0,0 +1,713 @@
+use pong_core::db;
+use pong_core::bracket::{DOUBLE_ELIMINATION, SINGLE_ELIMINATION};
+use pong_core::league::{HEAD_TO_HEAD, POINT_DIFFERENTIAL};
+use pong_core::models::{
+    CreateLeagueDto, CreateTournamentDto, ForfeitReason, Match, MatchAuditEntry, MatchEvent, MatchEventType, MatchResultSnapshot, PauseInterval,
+    PopulatedMatch, ScoreSnapshot, StartMatchDto,
+};
+use pong_core::rules::{SAME_PLAYER_BOTH_SIDES, UNKNOWN_GAME_MODE, UNKNOWN_PLAYER};
+use pong_core::{AppError, Repository};
+use chrono::{Duration, Utc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Each test gets its own in-memory database, with users 1 to 4 and the default game modes
+async fn test_repo() -> Repository {
+    static NEXT_DB: AtomicUsize = AtomicUsize::new(0);
+    let pool = db::open_in_memory(&format!("pong-core-test-{}", NEXT_DB.fetch_add(1, Ordering::SeqCst)))
+        .await
+        .unwrap();
+    let repo = Repository::new(pool);
+    sqlx::query("INSERT INTO users (name) VALUES ('Alice'), ('Bob'), ('Carol'), ('Dave')")
+        .execute(repo.pool())
+        .await
+        .unwrap();
+    repo.ensure_basic_game_mode().await.unwrap();
+    repo
+}
+
+fn singles(player1_id: i64, player2_id: i64) -> StartMatchDto {
+    StartMatchDto { player1_id, player2_id, game_mode_id: 1, ..Default::default() }
+}
+
+// Alice against Bob to 11 points, Alice serving first
+async fn seed_match(repo: &Repository) -> i64 {
+    let m = repo.start_match(singles(1, 2), None).await.unwrap();
+    repo.set_first_server(m.id, 1, None).await.unwrap();
+    m.id
+}
+
+async fn load_match(repo: &Repository, id: i64) -> Match {
+    sqlx::query_as("SELECT * FROM matches WHERE id = ?")
+        .bind(id)
+        .fetch_one(repo.pool())
+        .await
+        .unwrap()
+}
+
+async fn user_counters(repo: &Repository, id: i64) -> (i64, i64) {
+    sqlx::query_as("SELECT wins, matches_played FROM users WHERE id = ?")
+        .bind(id)
+        .fetch_one(repo.pool())
+        .await
+        .unwrap()
+}
+
+fn tournament(format: &str) -> CreateTournamentDto {
+    CreateTournamentDto { name: "Office Cup".to_string(), game_mode_id: 1, format: format.to_string(), grand_final_reset: true }
+}
+
+async fn win_match(repo: &Repository, m: &PopulatedMatch, winner_id: i64) {
+    repo.set_first_server(m.id, m.player1.id, None).await.unwrap();
+    for _ in 0..11 {
+        repo.add_point(m.id, winner_id, None, None, None).await.unwrap();
+    }
+}
+
+fn validation_message(result: Result<impl std::fmt::Debug, AppError>) -> String {
+    match result.unwrap_err() {
+        AppError::Validation { message, .. } => message,
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+fn validation_reason(result: Result<impl std::fmt::Debug, AppError>) -> Option<&'static str> {
+    match result.unwrap_err() {
+        AppError::Validation { reason, .. } => reason,
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn lineups_are_validated_before_starting() {
+    let repo = test_repo().await;
+    assert!(repo.start_match(singles(1, 2), None).await.is_ok());
+    let doubles = StartMatchDto { player3_id: Some(3), player4_id: Some(4), ..singles(1, 2) };
+    assert!(repo.start_match(doubles, None).await.is_ok());
+
+    assert_eq!(validation_reason(repo.start_match(singles(1, 1), None).await), Some(SAME_PLAYER_BOTH_SIDES));
+    assert_eq!(validation_reason(repo.start_match(singles(1, 99), None).await), Some(UNKNOWN_PLAYER));
+    assert_eq!(validation_message(repo.start_match(singles(1, 99), None).await), "User 99 does not exist");
+    let unknown_mode = StartMatchDto { game_mode_id: 42, ..singles(1, 2) };
+    assert_eq!(validation_reason(repo.start_match(unknown_mode, None).await), Some(UNKNOWN_GAME_MODE));
+}
+
+#[tokio::test]
+async fn foreign_keys_are_enforced() {
+    let repo = test_repo().await;
+    let insert = |game_mode_id: i64| {
+        sqlx::query("INSERT INTO matches (player1_id, player2_id, game_mode_id) VALUES (1, 2, ?)")
+            .bind(game_mode_id)
+            .execute(repo.pool())
+    };
+    // SQLITE_CONSTRAINT_FOREIGNKEY, the row is fine otherwise
+    let err = insert(99).await.unwrap_err();
+    assert_eq!(err.as_database_error().and_then(|e| e.code()).as_deref(), Some("787"));
+    assert!(insert(1).await.is_ok());
+}
+
+#[tokio::test]
+async fn concurrent_points_are_not_lost() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+    let version = load_match(&repo, match_id).await.version;
+
+    let mut handles = Vec::new();
+    for i in 0..10 {
+        let repo = repo.clone();
+        let player_id = if i % 2 == 0 { 1 } else { 2 };
+        handles.push(tokio::spawn(async move {
+            repo.add_point(match_id, player_id, None, None, None).await
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+
+    let m = load_match(&repo, match_id).await;
+    assert_eq!((m.score_p1, m.score_p2), (5, 5));
+    assert_eq!(m.version, version + 10);
+    let events: Vec<MatchEvent> = serde_json::from_str(&m.events).unwrap();
+    assert_eq!(events.iter().filter(|e| e.event_type.is_point()).count(), 10);
+}
+
+#[tokio::test]
+async fn concurrent_points_past_the_end_count_the_win_once() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+
+    let mut handles = Vec::new();
+    for _ in 0..15 {
+        let repo = repo.clone();
+        handles.push(tokio::spawn(async move {
+            repo.add_point(match_id, 1, None, None, None).await
+        }));
+    }
+    let mut rejected = 0;
+    for handle in handles {
+        if let Err(e) = handle.await.unwrap() {
+            assert!(matches!(e, AppError::RuleViolation(_)));
+            rejected += 1;
+        }
+    }
+
+    let m = load_match(&repo, match_id).await;
+    assert_eq!(m.status, "finished");
+    assert_eq!((m.score_p1, m.score_p2), (11, 0));
+    assert_eq!(rejected, 4);
+    assert_eq!(user_counters(&repo, 1).await, (1, 1));
+    assert_eq!(user_counters(&repo, 2).await, (0, 1));
+}
+
+#[tokio::test]
+async fn first_server_must_play_in_the_match() {
+    let repo = test_repo().await;
+    let m = repo.start_match(singles(1, 2), None).await.unwrap();
+
+    assert_eq!(validation_message(repo.set_first_server(m.id, 3, None).await), "User is not in this match");
+    assert_eq!(load_match(&repo, m.id).await.version, m.version);
+    let m = repo.set_first_server(m.id, 2, None).await.unwrap();
+    assert_eq!(m.current_server_id, Some(2));
+
+    // Only while the match is being played
+    for _ in 0..11 {
+        repo.add_point(m.id, 1, None, None, None).await.unwrap();
+    }
+    assert!(matches!(repo.set_first_server(m.id, 1, None).await, Err(AppError::RuleViolation(_))));
+    let abandoned = repo.start_match(singles(1, 2), None).await.unwrap();
+    repo.cancel_match(abandoned.id, None).await.unwrap();
+    assert!(matches!(repo.set_first_server(abandoned.id, 1, None).await, Err(AppError::RuleViolation(_))));
+    assert_eq!(load_match(&repo, abandoned.id).await.version, abandoned.version + 1);
+}
+
+#[tokio::test]
+async fn repeated_idempotency_key_scores_once() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+
+    let first = repo.add_point(match_id, 1, None, Some("press-1"), None).await.unwrap();
+    let repeated = repo.add_point(match_id, 1, None, Some("press-1"), None).await.unwrap();
+    assert_eq!(repeated.version, first.version);
+    assert_eq!((repeated.score.p1, repeated.score.p2), (1, 0));
+    assert_eq!(load_match(&repo, match_id).await.version, first.version);
+
+    // The key names the action, so it is never applied a second time
+    let reused = repo.add_point(match_id, 2, None, Some("press-1"), None).await.unwrap();
+    assert_eq!((reused.score.p1, reused.score.p2), (1, 0));
+    let other_match = repo.add_point(match_id + 1, 2, None, Some("press-1"), None).await;
+    assert!(matches!(other_match, Err(AppError::Validation { .. })));
+}
+
+#[tokio::test]
+async fn stale_expected_version_is_a_conflict() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+    let seen = load_match(&repo, match_id).await.version;
+
+    // Another client scores first, so a write based on `seen` is refused
+    let scored = repo.add_point(match_id, 2, None, None, Some(seen)).await.unwrap();
+    assert!(matches!(repo.add_point(match_id, 1, None, None, Some(seen)).await, Err(AppError::Conflict(_))));
+    assert!(matches!(repo.undo_last_point(match_id, None, Some(seen)).await, Err(AppError::Conflict(_))));
+    assert_eq!(load_match(&repo, match_id).await.version, scored.version);
+
+    let undone = repo.undo_last_point(match_id, None, Some(scored.version)).await.unwrap();
+    assert!(matches!(repo.redo_point(match_id, None, Some(scored.version)).await, Err(AppError::Conflict(_))));
+    let redone = repo.redo_point(match_id, None, Some(undone.version)).await.unwrap();
+    assert_eq!((redone.score.p1, redone.score.p2), (0, 1));
+
+    // The other match commands check it the same way
+    let stale = Some(seen);
+    assert!(matches!(repo.record_let(match_id, stale).await, Err(AppError::Conflict(_))));
+    assert!(matches!(repo.call_timeout(match_id, 1, stale).await, Err(AppError::Conflict(_))));
+    assert!(matches!(repo.pause_match(match_id, stale).await, Err(AppError::Conflict(_))));
+    assert!(matches!(repo.set_first_server(match_id, 2, stale).await, Err(AppError::Conflict(_))));
+    assert!(matches!(repo.forfeit_match(match_id, 1, ForfeitReason::Retirement, stale).await, Err(AppError::Conflict(_))));
+    assert!(matches!(repo.cancel_match(match_id, stale).await, Err(AppError::Conflict(_))));
+    assert_eq!(load_match(&repo, match_id).await.version, redone.version);
+    repo.cancel_match(match_id, Some(redone.version)).await.unwrap();
+}
+
+#[tokio::test]
+async fn undo_restores_the_score() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+    repo.add_point(match_id, 1, None, None, None).await.unwrap();
+    repo.add_point(match_id, 2, None, None, None).await.unwrap();
+
+    let undone = repo.undo_last_point(match_id, None, None).await.unwrap();
+    assert_eq!((undone.score.p1, undone.score.p2), (1, 0));
+    assert_eq!(undone.redo_count, 1);
+
+    // A double-fired redo is applied once
+    let redone = repo.redo_point(match_id, Some("redo-1"), None).await.unwrap();
+    assert_eq!((redone.score.p1, redone.score.p2), (1, 1));
+    let repeated = repo.redo_point(match_id, Some("redo-1"), None).await.unwrap();
+    assert_eq!((repeated.score.p1, repeated.score.p2), (1, 1));
+    assert_eq!(repeated.redo_count, 0);
+
+    // Undo and redo wait for a paused match to resume
+    repo.undo_last_point(match_id, None, None).await.unwrap();
+    repo.pause_match(match_id, None).await.unwrap();
+    assert!(matches!(repo.redo_point(match_id, None, None).await, Err(AppError::RuleViolation(_))));
+    assert!(matches!(repo.undo_last_point(match_id, None, None).await, Err(AppError::RuleViolation(_))));
+    repo.resume_match(match_id, None).await.unwrap();
+
+    // A new point clears the redo stack
+    let scored = repo.add_point(match_id, 1, None, None, None).await.unwrap();
+    assert_eq!((scored.score.p1, scored.score.p2, scored.redo_count), (2, 0, 0));
+    assert!(matches!(repo.redo_point(match_id, None, None).await, Err(AppError::RuleViolation(_))));
+
+    // An abandoned match keeps its events and redo stack
+    repo.cancel_match(match_id, None).await.unwrap();
+    assert!(matches!(repo.undo_last_point(match_id, None, None).await, Err(AppError::RuleViolation(_))));
+    let abandoned = repo.get_match(match_id).await.unwrap();
+    assert_eq!((abandoned.score.p1, abandoned.score.p2, abandoned.redo_count), (2, 0, 0));
+}
+
+#[tokio::test]
+async fn one_timeout_per_side() {
+    let repo = test_repo().await;
+    let doubles = StartMatchDto { player3_id: Some(3), player4_id: Some(4), ..singles(1, 2) };
+    let m = repo.start_match(doubles, None).await.unwrap();
+
+    repo.call_timeout(m.id, 1, None).await.unwrap();
+    // The partner's timeout is the same side's
+    assert!(matches!(repo.call_timeout(m.id, 3, None).await, Err(AppError::RuleViolation(_))));
+    let m = repo.call_timeout(m.id, 4, None).await.unwrap();
+    assert_eq!((m.timeouts_used.p1, m.timeouts_used.p2), (1, 1));
+    assert!(matches!(repo.call_timeout(m.id, 2, None).await, Err(AppError::RuleViolation(_))));
+}
+
+#[tokio::test]
+async fn paused_time_is_left_out_of_the_duration() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+
+    let paused = repo.pause_match(match_id, None).await.unwrap();
+    assert!(paused.is_paused);
+    assert!(matches!(repo.pause_match(match_id, None).await, Err(AppError::RuleViolation(_))));
+    assert!(matches!(repo.add_point(match_id, 1, None, None, None).await, Err(AppError::RuleViolation(_))));
+    let resumed = repo.resume_match(match_id, None).await.unwrap();
+    assert!(!resumed.is_paused);
+    assert!(matches!(repo.resume_match(match_id, None).await, Err(AppError::RuleViolation(_))));
+
+    // Started ten minutes ago, a minute's break, and paused again for the last four
+    let now = Utc::now();
+    let pauses = vec![
+        PauseInterval { started_at: (now - Duration::minutes(8)).timestamp_millis(), ended_at: Some((now - Duration::minutes(7)).timestamp_millis()) },
+        PauseInterval { started_at: (now - Duration::minutes(4)).timestamp_millis(), ended_at: None },
+    ];
+    sqlx::query("UPDATE matches SET start_time = ?, pauses = ? WHERE id = ?")
+        .bind(now - Duration::minutes(10))
+        .bind(serde_json::to_string(&pauses).unwrap())
+        .bind(match_id)
+        .execute(repo.pool())
+        .await
+        .unwrap();
+    let m = repo.get_match(match_id).await.unwrap();
+    assert!(m.is_paused);
+    assert!((300_000..=301_000).contains(&m.active_duration_ms), "{}", m.active_duration_ms);
+
+    // Forfeiting during the pause closes it, so the match is not left paused
+    let m = repo.forfeit_match(match_id, 2, ForfeitReason::Retirement, None).await.unwrap();
+    assert_eq!(m.status, "finished");
+    assert!((300_000..=301_000).contains(&m.active_duration_ms), "{}", m.active_duration_ms);
+    let stored: Vec<PauseInterval> = serde_json::from_str(&load_match(&repo, match_id).await.pauses).unwrap();
+    assert!(stored.iter().all(|p| p.ended_at.is_some()));
+}
+
+#[tokio::test]
+async fn edited_result_moves_the_win() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+    for _ in 0..11 {
+        repo.add_point(match_id, 1, None, None, None).await.unwrap();
+    }
+
+    let invalid = repo.edit_match_result(match_id, Some(vec![ScoreSnapshot { p1: 11, p2: 10 }]), None, None, None).await;
+    assert!(matches!(invalid, Err(AppError::Validation { .. })));
+
+    let m = repo
+        .edit_match_result(match_id, Some(vec![ScoreSnapshot { p1: 9, p2: 11 }]), None, Some("Scored on the wrong side".to_string()), None)
+        .await
+        .unwrap();
+    assert_eq!(m.winner.map(|u| u.id), Some(2));
+    assert_eq!((m.score.p1, m.score.p2), (9, 11));
+    assert_eq!(user_counters(&repo, 1).await, (0, 1));
+    assert_eq!(user_counters(&repo, 2).await, (1, 1));
+
+    let audit: Vec<MatchAuditEntry> = repo.get_match_audit(match_id).await.unwrap();
+    assert_eq!(audit.len(), 1);
+    let before: MatchResultSnapshot = serde_json::from_str(&audit[0].before).unwrap();
+    assert_eq!(before.winner_id, Some(1));
+}
+
+#[tokio::test]
+async fn statistics_follow_finished_matches() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+    for _ in 0..11 {
+        repo.add_point(match_id, 2, None, None, None).await.unwrap();
+    }
+
+    let stats = repo.get_user_statistics(2).await.unwrap();
+    assert_eq!((stats.wins, stats.losses), (1, 0));
+    assert_eq!((stats.points_scored, stats.points_conceded), (11, 0));
+    assert!(matches!(repo.get_user_statistics(99).await, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn aces_belong_to_the_player_not_the_partner() {
+    let repo = test_repo().await;
+    let doubles = StartMatchDto { player3_id: Some(3), player4_id: Some(4), ..singles(1, 2) };
+    let m = repo.start_match(doubles, None).await.unwrap();
+    repo.set_first_server(m.id, 1, None).await.unwrap();
+
+    // Carol serves two aces, Alice wins the other points, Bob's side errs once
+    repo.add_point(m.id, 3, Some(MatchEventType::Ace), None, None).await.unwrap();
+    repo.add_point(m.id, 3, Some(MatchEventType::Ace), None, None).await.unwrap();
+    repo.add_point(m.id, 1, Some(MatchEventType::UnforcedError), None, None).await.unwrap();
+    for _ in 0..8 {
+        repo.add_point(m.id, 1, None, None, None).await.unwrap();
+    }
+
+    let carol = repo.get_user_statistics(3).await.unwrap();
+    assert_eq!((carol.aces, carol.aces_per_match), (2, 2.0));
+    assert_eq!(carol.points_won_by.len(), 1);
+    let alice = repo.get_user_statistics(1).await.unwrap();
+    assert_eq!(alice.aces, 0);
+    assert!(alice.points_won_by.iter().all(|s| s.reason != MatchEventType::Ace));
+    // The error is the other team's, shared by both partners
+    assert_eq!(repo.get_user_statistics(2).await.unwrap().errors, 1);
+    assert_eq!(repo.get_user_statistics(4).await.unwrap().errors, 1);
+    assert_eq!(alice.errors, 0);
+}
+
+#[tokio::test]
+async fn forfeits_count_in_the_statistics() {
+    let repo = test_repo().await;
+    sqlx::query("UPDATE game_modes SET games_to_win = 2 WHERE id = 1").execute(repo.pool()).await.unwrap();
+
+    // Alice takes the first game, then retires at 3-9 in the second
+    let match_id = seed_match(&repo).await;
+    for _ in 0..11 {
+        repo.add_point(match_id, 1, None, None, None).await.unwrap();
+    }
+    for player_id in [1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2] {
+        repo.add_point(match_id, player_id, None, None, None).await.unwrap();
+    }
+    let m = repo.forfeit_match(match_id, 1, ForfeitReason::Retirement, None).await.unwrap();
+    assert_eq!(m.winner.map(|u| u.id), Some(2));
+
+    // Carol's opponent never turns up
+    let walkover = repo.start_match(singles(3, 4), None).await.unwrap();
+    repo.forfeit_match(walkover.id, 4, ForfeitReason::Walkover, None).await.unwrap();
+
+    // The game in progress counts in the points, the win still counts as a win
+    let bob = repo.get_user_statistics(2).await.unwrap();
+    assert_eq!((bob.wins, bob.losses, bob.forfeit_wins, bob.forfeit_losses), (1, 0, 1, 0));
+    assert_eq!((bob.points_scored, bob.points_conceded), (9, 14));
+    let alice = repo.get_user_statistics(1).await.unwrap();
+    assert_eq!((alice.wins, alice.losses, alice.forfeit_wins, alice.forfeit_losses), (0, 1, 0, 1));
+    assert_eq!((alice.points_scored, alice.points_conceded), (14, 9));
+    assert_eq!(alice.forfeits.len(), 1);
+    assert_eq!((alice.forfeits[0].reason, alice.forfeits[0].wins, alice.forfeits[0].losses), (ForfeitReason::Retirement, 0, 1));
+
+    let carol = repo.get_user_statistics(3).await.unwrap();
+    assert_eq!((carol.wins, carol.forfeit_wins, carol.points_scored, carol.points_conceded), (1, 1, 0, 0));
+    assert_eq!(carol.forfeits[0].reason, ForfeitReason::Walkover);
+    assert_eq!(user_counters(&repo, 3).await, (1, 1));
+    assert_eq!(user_counters(&repo, 4).await, (0, 1));
+}
+
+#[tokio::test]
+async fn closed_matches_say_why_they_refuse_changes() {
+    let repo = test_repo().await;
+    let rule_message = |result: Result<PopulatedMatch, AppError>| match result.unwrap_err() {
+        AppError::RuleViolation(message) => message,
+        other => panic!("expected a rule violation, got {:?}", other),
+    };
+
+    let finished = seed_match(&repo).await;
+    for _ in 0..11 {
+        repo.add_point(finished, 1, None, None, None).await.unwrap();
+    }
+    assert_eq!(rule_message(repo.record_let(finished, None).await), "Match is finished");
+    assert_eq!(rule_message(repo.pause_match(finished, None).await), "Match is finished");
+
+    let abandoned = seed_match(&repo).await;
+    repo.pause_match(abandoned, None).await.unwrap();
+    repo.cancel_match(abandoned, None).await.unwrap();
+    assert_eq!(rule_message(repo.add_point(abandoned, 1, None, None, None).await), "Match is abandoned");
+    assert_eq!(rule_message(repo.call_timeout(abandoned, 1, None).await), "Match is abandoned");
+    assert_eq!(rule_message(repo.forfeit_match(abandoned, 1, ForfeitReason::Walkover, None).await), "Match is abandoned");
+    assert_eq!(rule_message(repo.resume_match(abandoned, None).await), "Match is abandoned");
+}
+
+#[tokio::test]
+async fn finished_matches_cannot_be_cancelled() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+    for _ in 0..11 {
+        repo.add_point(match_id, 1, None, None, None).await.unwrap();
+    }
+
+    assert!(matches!(repo.cancel_match(match_id, None).await, Err(AppError::RuleViolation(_))));
+    assert_eq!(load_match(&repo, match_id).await.status, "finished");
+    assert_eq!(user_counters(&repo, 1).await, (1, 1));
+    assert!(matches!(repo.cancel_match(99, None).await, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn recompute_fixes_drifted_counters() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+    for _ in 0..11 {
+        repo.add_point(match_id, 1, None, None, None).await.unwrap();
+    }
+    sqlx::query("UPDATE users SET wins = 7, matches_played = 3 WHERE id = 2")
+        .execute(repo.pool())
+        .await
+        .unwrap();
+
+    let version = load_match(&repo, match_id).await.version;
+
+    let summary = repo.recompute_statistics().await.unwrap();
+    assert_eq!(summary.matches_rebuilt, 1);
+    assert_eq!(summary.results_changed, 0);
+    // A match that was already right is not rewritten
+    assert_eq!(load_match(&repo, match_id).await.version, version);
+    assert_eq!(user_counters(&repo, 1).await, (1, 1));
+    assert_eq!(user_counters(&repo, 2).await, (0, 1));
+    assert_eq!(user_counters(&repo, 3).await, (0, 0));
+
+    let leaderboard = repo.get_leaderboard().await.unwrap();
+    assert_eq!(leaderboard[0].id, 1);
+    let finished = repo.list_matches(Some("finished"), 10).await.unwrap();
+    assert_eq!(finished.len(), 1);
+    assert!(repo.list_matches(Some("in_progress"), 10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn recomputed_results_move_the_bracket() {
+    let repo = test_repo().await;
+    let cup = repo.create_tournament(tournament(SINGLE_ELIMINATION)).await.unwrap();
+    for user_id in 1..=4 {
+        repo.register_tournament_player(cup.id, user_id).await.unwrap();
+    }
+    repo.start_tournament(cup.id).await.unwrap();
+    let first = repo.start_next_bracket_match(cup.id).await.unwrap();
+    win_match(&repo, &first, 1).await;
+    let second = repo.start_next_bracket_match(cup.id).await.unwrap();
+
+    // The event log says Dave won every point, the stored result still says Alice
+    let stored = load_match(&repo, first.id).await;
+    sqlx::query("UPDATE matches SET events = ? WHERE id = ?")
+        .bind(stored.events.replace("\"elementId\":\"1\"", "\"elementId\":\"4\""))
+        .bind(first.id)
+        .execute(repo.pool())
+        .await
+        .unwrap();
+
+    let summary = repo.recompute_statistics().await.unwrap();
+    assert_eq!(summary.results_changed, 1);
+    assert_eq!(load_match(&repo, first.id).await.winner_id, Some(4));
+    assert_eq!(load_match(&repo, second.id).await.version, second.version);
+    let bracket = repo.get_bracket(cup.id).await.unwrap();
+    let final_slot = bracket.matches.iter().find(|m| m.round == 2).unwrap();
+    assert_eq!(final_slot.player1_id, Some(4));
+}
+
+#[tokio::test]
+async fn live_feed_follows_the_score() {
+    let repo = test_repo().await;
+    let match_id = seed_match(&repo).await;
+    let mut feed = repo.live().subscribe();
+
+    repo.add_point(match_id, 1, None, None, None).await.unwrap();
+    repo.record_let(match_id, None).await.unwrap();
+    repo.undo_last_point(match_id, None, None).await.unwrap();
+    repo.redo_point(match_id, None, None).await.unwrap();
+    repo.pause_match(match_id, None).await.unwrap();
+    repo.resume_match(match_id, None).await.unwrap();
+    repo.call_timeout(match_id, 2, None).await.unwrap();
+    repo.cancel_match(match_id, None).await.unwrap();
+
+    let scored = feed.recv().await.unwrap();
+    assert_eq!((scored.id, scored.score.p1), (match_id, 1));
+    // Every change is published, including the ones that leave the score alone
+    let with_let = feed.recv().await.unwrap();
+    assert_eq!(with_let.events.last().map(|e| e.event_type), Some(MatchEventType::Let));
+    assert_eq!(feed.recv().await.unwrap().redo_count, 1);
+    assert_eq!(feed.recv().await.unwrap().redo_count, 0);
+    assert!(feed.recv().await.unwrap().is_paused);
+    assert!(!feed.recv().await.unwrap().is_paused);
+    assert_eq!(feed.recv().await.unwrap().timeouts_used.p2, 1);
+    assert_eq!(feed.recv().await.unwrap().status, "abandoned");
+    assert!(feed.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn bracket_winners_advance_to_the_final() {
+    let repo = test_repo().await;
+    sqlx::query("INSERT INTO users (name) VALUES ('Eve')").execute(repo.pool()).await.unwrap();
+    sqlx::query("UPDATE users SET wins = 3, matches_played = 4 WHERE id = 3").execute(repo.pool()).await.unwrap();
+
+    let cup = repo.create_tournament(tournament(SINGLE_ELIMINATION)).await.unwrap();
+    for user_id in 1..=5 {
+        repo.register_tournament_player(cup.id, user_id).await.unwrap();
+    }
+    assert!(matches!(repo.register_tournament_player(cup.id, 1).await, Err(AppError::Validation { .. })));
+
+    // Carol has the best win rate; with 5 players the top 3 seeds get a bye
+    let bracket = repo.start_tournament(cup.id).await.unwrap();
+    assert_eq!(bracket.entries[0].user_id, 3);
+    assert_eq!(bracket.matches.len(), 7);
+    assert_eq!(bracket.matches.iter().filter(|m| m.round == 1 && m.winner_id.is_some()).count(), 3);
+    assert!(matches!(repo.register_tournament_player(cup.id, 4).await, Err(AppError::RuleViolation(_))));
+
+    // Player 1 of every bracket match wins it
+    let mut played = 0;
+    while let Ok(m) = repo.start_next_bracket_match(cup.id).await {
+        win_match(&repo, &m, m.player1.id).await;
+        played += 1;
+    }
+    assert_eq!(played, 4);
+
+    let bracket = repo.get_bracket(cup.id).await.unwrap();
+    assert_eq!(bracket.tournament.status, "finished");
+    assert_eq!(bracket.tournament.winner_id, Some(3));
+}
+
+#[tokio::test]
+async fn redone_deciding_point_restores_the_bracket() {
+    let repo = test_repo().await;
+    let cup = repo.create_tournament(tournament(SINGLE_ELIMINATION)).await.unwrap();
+    for user_id in 1..=4 {
+        repo.register_tournament_player(cup.id, user_id).await.unwrap();
+    }
+    repo.start_tournament(cup.id).await.unwrap();
+
+    // Alice beats Dave, Bob beats Carol, Alice wins the final
+    let first = repo.start_next_bracket_match(cup.id).await.unwrap();
+    win_match(&repo, &first, 1).await;
+    let second = repo.start_next_bracket_match(cup.id).await.unwrap();
+    win_match(&repo, &second, 2).await;
+    let final_match = repo.start_next_bracket_match(cup.id).await.unwrap();
+    win_match(&repo, &final_match, 1).await;
+    assert_eq!(repo.get_bracket(cup.id).await.unwrap().tournament.winner_id, Some(1));
+
+    // Undoing the semi-final's deciding point empties the final slot but keeps its match linked
+    repo.undo_last_point(first.id, None, None).await.unwrap();
+    let bracket = repo.get_bracket(cup.id).await.unwrap();
+    let final_slot = bracket.matches.iter().find(|m| m.round == 2).unwrap();
+    assert_eq!((final_slot.player1_id, final_slot.player2_id, final_slot.winner_id), (None, Some(2), None));
+    assert_eq!(final_slot.match_id, Some(final_match.id));
+    assert_eq!((bracket.tournament.status.as_str(), bracket.tournament.winner_id), ("in_progress", None));
+    assert!(repo.start_next_bracket_match(cup.id).await.is_err());
+
+    // Redoing it brings the final's result back
+    repo.redo_point(first.id, None, None).await.unwrap();
+    let bracket = repo.get_bracket(cup.id).await.unwrap();
+    let final_slot = bracket.matches.iter().find(|m| m.round == 2).unwrap();
+    assert_eq!((final_slot.player1_id, final_slot.player2_id, final_slot.winner_id), (Some(1), Some(2), Some(1)));
+    assert_eq!(final_slot.match_id, Some(final_match.id));
+    assert_eq!((bracket.tournament.status.as_str(), bracket.tournament.winner_id), ("finished", Some(1)));
+}
+
+#[tokio::test]
+async fn double_elimination_follows_corrections_and_undo() {
+    let repo = test_repo().await;
+    let cup = repo.create_tournament(tournament(DOUBLE_ELIMINATION)).await.unwrap();
+    for user_id in 1..=4 {
+        repo.register_tournament_player(cup.id, user_id).await.unwrap();
+    }
+    let bracket = repo.start_tournament(cup.id).await.unwrap();
+    assert_eq!(bracket.matches.len(), 7);
+
+    // Seeds follow registration: Alice beats Dave and Bob beats Carol
+    let first = repo.start_next_bracket_match(cup.id).await.unwrap();
+    win_match(&repo, &first, 1).await;
+    let second = repo.start_next_bracket_match(cup.id).await.unwrap();
+    win_match(&repo, &second, 2).await;
+
+    let bracket = repo.get_bracket(cup.id).await.unwrap();
+    let losers_opening = bracket.matches.iter().find(|m| m.bracket == "losers" && m.round == 1).unwrap();
+    assert_eq!((losers_opening.player1_id, losers_opening.player2_id), (Some(4), Some(3)));
+
+    // Correcting the first result swaps who drops into the losers bracket
+    repo.edit_match_result(first.id, Some(vec![ScoreSnapshot { p1: 5, p2: 11 }]), None, None, None).await.unwrap();
+    let bracket = repo.get_bracket(cup.id).await.unwrap();
+    let losers_opening = bracket.matches.iter().find(|m| m.bracket == "losers" && m.round == 1).unwrap();
+    assert_eq!((losers_opening.player1_id, losers_opening.player2_id), (Some(1), Some(3)));
+    let winners_final = bracket.matches.iter().find(|m| m.bracket == "winners" && m.round == 2).unwrap();
+    assert_eq!((winners_final.player1_id, winners_final.player2_id), (Some(4), Some(2)));
+
+    // Undoing the deciding point reopens the match, and the slots after it empty again
+    repo.undo_last_point(second.id, None, None).await.unwrap();
+    let bracket = repo.get_bracket(cup.id).await.unwrap();
+    let winners_final = bracket.matches.iter().find(|m| m.bracket == "winners" && m.round == 2).unwrap();
+    assert_eq!((winners_final.player1_id, winners_final.player2_id), (Some(4), None));
+    assert!(bracket.matches.iter().find(|m| m.match_id == Some(second.id)).unwrap().winner_id.is_none());
+    repo.add_point(second.id, 2, None, None, None).await.unwrap();
+
+    // Dave wins the winners final, Bob comes back through the losers bracket and wins the grand final
+    // and the reset: winners final, losers rounds 1 and 2, grand final, reset
+    for winner in [4, 1, 2, 2, 2] {
+        let m = repo.start_next_bracket_match(cup.id).await.unwrap();
+        win_match(&repo, &m, winner).await;
+    }
+    assert!(repo.start_next_bracket_match(cup.id).await.is_err());
+    let bracket = repo.get_bracket(cup.id).await.unwrap();
+    assert_eq!(bracket.tournament.status, "finished");
+    assert_eq!(bracket.tournament.winner_id, Some(2));
+}
+
+#[tokio::test]
+async fn league_standings_follow_linked_matches() {
+    let repo = test_repo().await;
+    let dto = CreateLeagueDto {
+        name: "October League".to_string(),
+        game_mode_id: 1,
+        double_round: false,
+        tie_breakers: vec![HEAD_TO_HEAD.to_string(), POINT_DIFFERENTIAL.to_string()],
+    };
+    let league = repo.create_league(dto).await.unwrap();
+    for user_id in 1..=3 {
+        repo.register_league_player(league.id, user_id).await.unwrap();
+    }
+    let table = repo.start_league(league.id).await.unwrap();
+    assert_eq!(table.fixtures.len(), 3);
+    assert!(table.fixtures.iter().all(|f| f.status == "pending"));
+    assert!(matches!(repo.register_league_player(league.id, 4).await, Err(AppError::RuleViolation(_))));
+
+    // A rock-paper-scissors month: Alice beats Bob, Bob beats Carol, Carol beats Alice
+    let beats = |a: i64, b: i64| matches!((a, b), (1, 2) | (2, 3) | (3, 1));
+    let mut alice_bob = None;
+    for fixture in &table.fixtures {
+        let m = repo.start_league_fixture(fixture.id).await.unwrap();
+        assert!(matches!(repo.start_league_fixture(fixture.id).await, Err(AppError::RuleViolation(_))));
+        let winner = if beats(m.player1.id, m.player2.id) { m.player1.id } else { m.player2.id };
+        win_match(&repo, &m, winner).await;
+        if winner == 1 {
+            alice_bob = Some(m);
+        }
+    }
+    let table = repo.get_league(league.id).await.unwrap();
+    assert_eq!(table.league.status, "finished");
+    assert!(table.standings.iter().all(|s| s.won == 1 && s.point_differential == 0));
+
+    // Level on wins and head-to-head, so the corrected 11-9 decides on differential
+    let alice_bob = alice_bob.unwrap();
+    let score = if alice_bob.player1.id == 1 { ScoreSnapshot { p1: 11, p2: 9 } } else { ScoreSnapshot { p1: 9, p2: 11 } };
+    repo.edit_match_result(alice_bob.id, Some(vec![score]), None, None, None).await.unwrap();
+    let table = repo.get_league(league.id).await.unwrap();
+    let order: Vec<(i64, i64)> = table.standings.iter().map(|s| (s.user_id, s.point_differential)).collect();
+    assert_eq!(order, vec![(2, 9), (3, 0), (1, -9)]);
+
+    // Undoing the deciding point reopens the league and takes the result out of the table
+    repo.undo_last_point(alice_bob.id, None, None).await.unwrap();
+    let table = repo.get_league(league.id).await.unwrap();
+    assert_eq!(table.league.status, "in_progress");
+    assert_eq!(table.fixtures.iter().filter(|f| f.status == "in_progress").count(), 1);
+    assert_eq!(table.standings.iter().map(|s| s.played).sum::<i64>(), 4);
+}
@@ -0,0 +1,258 @@
+// Admin tool for the Pong Ping database.
+// Opens the same pingpong.db as the app (or --db / PONG_PING_DB) and runs the same
+// migrations and seed on open, then goes through pong-core like the Tauri commands do.
+
+mod output;
+
+use clap::{Args, Parser, Subcommand};
+use output::Format;
+use pong_core::models::{CreateGameModeDto, MatchEventType, StartMatchDto};
+use pong_core::{scoring, AppError, Repository};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+// Tauri keeps the database in <data dir>/<bundle identifier>
+const APP_IDENTIFIER: &str = "com.davide.pingpong";
+
+#[derive(Parser)]
+#[command(name = "pong-ping-cli", version, about = "Manage the Pong Ping database from the terminal")]
+struct Cli {
+    /// Path to pingpong.db (defaults to the app's own database)
+    #[arg(long, global = true, env = "PONG_PING_DB")]
+    db: Option<PathBuf>,
+
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List users
+    Users,
+    /// List matches, newest first
+    Matches {
+        /// in_progress, finished or abandoned
+        #[arg(long)]
+        status: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+    /// List or create game modes
+    #[command(subcommand)]
+    Modes(ModesCommand),
+    /// Start, show and score a match
+    #[command(subcommand)]
+    Match(MatchCommand),
+    /// Users ranked by wins
+    Leaderboard {
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Maintenance of the cached statistics
+    #[command(subcommand)]
+    Stats(StatsCommand),
+}
+
+#[derive(Subcommand)]
+enum ModesCommand {
+    List,
+    Create(CreateMode),
+}
+
+#[derive(Args)]
+struct CreateMode {
+    #[arg(long)]
+    name: String,
+    #[arg(long, default_value_t = 11)]
+    points_to_win: i64,
+    #[arg(long, default_value_t = 2)]
+    serves_before_change: i64,
+    #[arg(long, default_value_t = 1)]
+    serves_in_deuce: i64,
+    #[arg(long, default_value_t = 1)]
+    games_to_win: i64,
+    /// classic, capped, golden_point or first_to
+    #[arg(long, default_value = scoring::CLASSIC)]
+    scoring_type: String,
+    #[arg(long)]
+    points_cap: Option<i64>,
+    #[arg(long)]
+    no_deuce: bool,
+    #[arg(long)]
+    description: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum MatchCommand {
+    Start(StartMatch),
+    Show { id: i64 },
+    /// Award a point to a player
+    Point {
+        id: i64,
+        player_id: i64,
+        /// ace, service_fault, unforced_error, edge_ball, net or penalty
+        #[arg(long, value_parser = parse_reason)]
+        reason: Option<MatchEventType>,
+    },
+    Undo { id: i64 },
+}
+
+#[derive(Args)]
+struct StartMatch {
+    #[arg(long)]
+    p1: i64,
+    #[arg(long)]
+    p2: i64,
+    /// Partner of p1, for doubles
+    #[arg(long)]
+    p3: Option<i64>,
+    /// Partner of p2, for doubles
+    #[arg(long)]
+    p4: Option<i64>,
+    #[arg(long)]
+    mode: i64,
+    #[arg(long)]
+    first_server: Option<i64>,
+    #[arg(long)]
+    handicap_p1: Option<i64>,
+    #[arg(long)]
+    handicap_p2: Option<i64>,
+}
+
+#[derive(Subcommand)]
+enum StatsCommand {
+    /// Replay every match and recount wins and matches played
+    Recompute,
+}
+
+fn parse_reason(value: &str) -> Result<MatchEventType, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).map_err(|_| format!("unknown reason: {}", value))
+}
+
+fn default_db_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join("pingpong.db"))
+}
+
+async fn open(db: Option<PathBuf>) -> Result<Repository, AppError> {
+    let path = match db {
+        Some(path) => path,
+        // Never create a stray database where the app would not look for it
+        None => default_db_path()
+            .filter(|path| path.exists())
+            .ok_or(AppError::NotFound("No app database found, pass --db".to_string()))?,
+    };
+    let db_url = format!("sqlite://{}", path.to_string_lossy());
+
+    let pool = pong_core::db::open(&db_url).await.map_err(AppError::Database)?;
+    let repo = Repository::new(pool);
+    repo.ensure_basic_game_mode().await?;
+    Ok(repo)
+}
+
+async fn run(cli: Cli) -> Result<(), AppError> {
+    let repo = open(cli.db).await?;
+    let format = cli.format;
+
+    match cli.command {
+        Command::Users => {
+            let users = repo.get_users().await?;
+            match format {
+                Format::Json => output::print_json(&users),
+                Format::Table => println!("{}", output::users_table(&users)),
+            }
+        }
+        Command::Matches { status, limit } => {
+            let matches = repo.list_matches(status.as_deref(), limit).await?;
+            match format {
+                Format::Json => output::print_json(&matches),
+                Format::Table => println!("{}", output::matches_table(&matches)),
+            }
+        }
+        Command::Modes(ModesCommand::List) => {
+            let modes = repo.get_game_modes().await?;
+            match format {
+                Format::Json => output::print_json(&modes),
+                Format::Table => println!("{}", output::modes_table(&modes)),
+            }
+        }
+        Command::Modes(ModesCommand::Create(mode)) => {
+            let created = repo.create_game_mode(CreateGameModeDto {
+                name: mode.name,
+                points_to_win: mode.points_to_win,
+                serves_before_change: mode.serves_before_change,
+                rules_description: mode.description,
+                is_deuce_enabled: !mode.no_deuce,
+                serves_in_deuce: mode.serves_in_deuce,
+                serve_type: "free".to_string(),
+                games_to_win: mode.games_to_win,
+                scoring_type: mode.scoring_type,
+                points_cap: mode.points_cap,
+            }).await?;
+            match format {
+                Format::Json => output::print_json(&created),
+                Format::Table => println!("{}", output::modes_table(&[created])),
+            }
+        }
+        Command::Match(command) => {
+            let m = match command {
+                MatchCommand::Start(start) => {
+                    let m = repo.start_match(StartMatchDto {
+                        player1_id: start.p1,
+                        player2_id: start.p2,
+                        player3_id: start.p3,
+                        player4_id: start.p4,
+                        game_mode_id: start.mode,
+                        handicap_p1: start.handicap_p1,
+                        handicap_p2: start.handicap_p2,
+                        ..Default::default()
+                    }, None).await?;
+                    match start.first_server {
+                        Some(server) => repo.set_first_server(m.id, server, None).await?,
+                        None => m,
+                    }
+                }
+                MatchCommand::Show { id } => repo.get_match(id).await?,
+                MatchCommand::Point { id, player_id, reason } => repo.add_point(id, player_id, reason, None, None).await?,
+                MatchCommand::Undo { id } => repo.undo_last_point(id, None, None).await?,
+            };
+            match format {
+                Format::Json => output::print_json(&m),
+                Format::Table => println!("{}", output::match_summary(&m)),
+            }
+        }
+        Command::Leaderboard { limit } => {
+            let mut users = repo.get_leaderboard().await?;
+            if let Some(limit) = limit {
+                users.truncate(limit);
+            }
+            match format {
+                Format::Json => output::print_json(&users),
+                Format::Table => println!("{}", output::leaderboard_table(&users)),
+            }
+        }
+        Command::Stats(StatsCommand::Recompute) => {
+            let summary = repo.recompute_statistics().await?;
+            match format {
+                Format::Json => output::print_json(&summary),
+                Format::Table => println!("{}", output::recompute_summary(&summary)),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let format = cli.format;
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            output::print_error(format, &e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,165 @@
+// Table and JSON rendering for the CLI.
+// Tables are plain, space-aligned columns so they can be piped into grep/awk.
+
+use pong_core::models::{GameMode, PopulatedMatch, RecomputeSummary, User};
+use pong_core::AppError;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+pub fn print_json<T: Serialize + ?Sized>(value: &T) {
+    println!("{}", serde_json::to_string_pretty(value).expect("CLI output is always serializable"));
+}
+
+// Errors go to stderr, as { code, message } in JSON mode
+pub fn print_error(format: Format, err: &AppError) {
+    match format {
+        Format::Json => eprintln!("{}", serde_json::to_string(err).expect("AppError is always serializable")),
+        Format::Table => eprintln!("error: {}", err),
+    }
+}
+
+pub fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells.iter().zip(&widths).map(|(c, w)| format!("{:<w$}", c, w = *w)).collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    let mut out = vec![line(headers.to_vec())];
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    out.push(line(rule.iter().map(String::as_str).collect()));
+    for row in rows {
+        out.push(line(row.iter().map(String::as_str).collect()));
+    }
+    out.join("\n")
+}
+
+fn win_rate(user: &User) -> String {
+    if user.matches_played > 0 {
+        format!("{:.0}%", user.wins as f64 / user.matches_played as f64 * 100.0)
+    } else {
+        "-".to_string()
+    }
+}
+
+pub fn users_table(users: &[User]) -> String {
+    let rows: Vec<Vec<String>> = users.iter()
+        .map(|u| vec![
+            u.id.to_string(),
+            u.name.clone(),
+            u.fun_nickname.clone().unwrap_or_default(),
+            u.wins.to_string(),
+            u.matches_played.to_string(),
+        ])
+        .collect();
+    table(&["ID", "NAME", "NICKNAME", "WINS", "PLAYED"], &rows)
+}
+
+pub fn leaderboard_table(users: &[User]) -> String {
+    let rows: Vec<Vec<String>> = users.iter()
+        .enumerate()
+        .map(|(rank, u)| vec![
+            (rank + 1).to_string(),
+            u.name.clone(),
+            u.wins.to_string(),
+            (u.matches_played - u.wins).to_string(),
+            win_rate(u),
+        ])
+        .collect();
+    table(&["#", "NAME", "WINS", "LOSSES", "WIN RATE"], &rows)
+}
+
+pub fn modes_table(modes: &[GameMode]) -> String {
+    let rows: Vec<Vec<String>> = modes.iter()
+        .map(|m| vec![
+            m.id.to_string(),
+            m.name.clone(),
+            m.points_to_win.to_string(),
+            m.games_to_win.to_string(),
+            m.scoring_type.clone(),
+            m.points_cap.map(|c| c.to_string()).unwrap_or_default(),
+        ])
+        .collect();
+    table(&["ID", "NAME", "POINTS", "GAMES TO WIN", "SCORING", "CAP"], &rows)
+}
+
+fn side_names(captain: &User, partner: &Option<User>) -> String {
+    match partner {
+        Some(p) => format!("{} / {}", captain.name, p.name),
+        None => captain.name.clone(),
+    }
+}
+
+pub fn matches_table(matches: &[PopulatedMatch]) -> String {
+    let rows: Vec<Vec<String>> = matches.iter()
+        .map(|m| vec![
+            m.id.to_string(),
+            m.status.clone(),
+            side_names(&m.player1, &m.player3),
+            side_names(&m.player2, &m.player4),
+            format!("{}-{}", m.score.p1, m.score.p2),
+            format!("{}-{}", m.games_won.p1, m.games_won.p2),
+            m.game_mode.name.clone(),
+        ])
+        .collect();
+    table(&["ID", "STATUS", "SIDE 1", "SIDE 2", "SCORE", "GAMES", "MODE"], &rows)
+}
+
+pub fn match_summary(m: &PopulatedMatch) -> String {
+    let mut lines = vec![
+        format!("Match {} ({}, {})", m.id, m.game_mode.name, m.status),
+        format!(
+            "{}  {} - {}  {}",
+            side_names(&m.player1, &m.player3),
+            m.score.p1,
+            m.score.p2,
+            side_names(&m.player2, &m.player4),
+        ),
+    ];
+    if m.match_rules.games_to_win > 1 {
+        lines.push(format!("Games: {}-{}", m.games_won.p1, m.games_won.p2));
+    }
+    let players = [Some(&m.player1), Some(&m.player2), m.player3.as_ref(), m.player4.as_ref()];
+    let name_of = |id: i64| players.iter().flatten().find(|u| u.id == id).map(|u| u.name.clone());
+    if let Some(winner) = &m.winner {
+        lines.push(format!("Winner: {}", winner.name));
+    } else if let Some(server) = m.current_server_id.and_then(name_of) {
+        lines.push(format!("Serving: {} ({} left)", server, m.serves_remaining));
+    }
+    if m.is_paused {
+        lines.push("Paused".to_string());
+    }
+    lines.join("\n")
+}
+
+pub fn recompute_summary(summary: &RecomputeSummary) -> String {
+    format!(
+        "Rebuilt {} matches ({} with a different winner), recounted {} users",
+        summary.matches_rebuilt, summary.results_changed, summary.users_updated
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_are_aligned() {
+        let rows = vec![
+            vec!["1".to_string(), "Alice".to_string()],
+            vec!["10".to_string(), "Bo".to_string()],
+        ];
+        assert_eq!(table(&["ID", "NAME"], &rows), "ID  NAME\n--  -----\n1   Alice\n10  Bo");
+    }
+}
@@ -0,0 +1,90 @@
+// Events emitted to every window after a command changes something, so a second window
+// (a projector scoreboard, another screen) can refresh without polling.
+// Match events come from the repository's live feed, so REST API writes reach the windows too.
+// Names and payloads are mirrored in src/services/eventService.ts.
+
+use pong_core::models::{KeyBinding, PopulatedMatch, User};
+use pong_core::Repository;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
+
+pub const MATCH_UPDATED: &str = "match-updated";
+pub const MATCH_CANCELLED: &str = "match-cancelled";
+pub const USER_CREATED: &str = "user-created";
+pub const USER_UPDATED: &str = "user-updated";
+pub const KEY_BINDING_CHANGED: &str = "key-binding-changed";
+pub const KEY_BINDING_DELETED: &str = "key-binding-deleted";
+pub const KEY_BINDINGS_RESET: &str = "key-bindings-reset";
+pub const SETTING_CHANGED: &str = "setting-changed";
+pub const SCOREBOARD_TARGET: &str = "scoreboard-target";
+
+pub enum AppEvent<'a> {
+    // Any committed change to a match, see Repository::live
+    MatchUpdated(&'a PopulatedMatch),
+    MatchCancelled(i64),
+    UserCreated(&'a User),
+    UserUpdated(&'a User),
+    KeyBindingChanged(&'a KeyBinding),
+    KeyBindingDeleted(i64),
+    // Payload is the full list of bindings after the reset
+    KeyBindingsReset(&'a [KeyBinding]),
+    SettingChanged { key: &'a str, value: &'a str },
+    // The scoreboard window should switch to this match
+    ScoreboardTarget(i64),
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MatchIdPayload {
+    match_id: i64,
+}
+
+#[derive(Clone, Serialize)]
+struct IdPayload {
+    id: i64,
+}
+
+#[derive(Clone, Serialize)]
+struct SettingChangedPayload<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+// The command already succeeded, so a failed emit is only logged
+pub fn emit(app: &AppHandle, event: AppEvent) {
+    let result = match event {
+        AppEvent::MatchUpdated(m) => app.emit(MATCH_UPDATED, m),
+        AppEvent::MatchCancelled(match_id) => app.emit(MATCH_CANCELLED, MatchIdPayload { match_id }),
+        AppEvent::UserCreated(user) => app.emit(USER_CREATED, user),
+        AppEvent::UserUpdated(user) => app.emit(USER_UPDATED, user),
+        AppEvent::KeyBindingChanged(binding) => app.emit(KEY_BINDING_CHANGED, binding),
+        AppEvent::KeyBindingDeleted(id) => app.emit(KEY_BINDING_DELETED, IdPayload { id }),
+        AppEvent::KeyBindingsReset(bindings) => app.emit(KEY_BINDINGS_RESET, bindings),
+        AppEvent::SettingChanged { key, value } => app.emit(SETTING_CHANGED, SettingChangedPayload { key, value }),
+        AppEvent::ScoreboardTarget(match_id) => app.emit(SCOREBOARD_TARGET, MatchIdPayload { match_id }),
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to emit event: {}", e);
+    }
+}
+
+// Emits every match change the repository publishes, whoever made it; started once at launch
+pub fn forward_live_feed(app: AppHandle, repo: &Repository) {
+    let mut feed = repo.live().subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match feed.recv().await {
+                Ok(m) => {
+                    emit(&app, AppEvent::MatchUpdated(&m));
+                    if m.status == "abandoned" {
+                        emit(&app, AppEvent::MatchCancelled(m.id));
+                    }
+                }
+                // Each update is the full match, so missing some only skips intermediate states
+                Err(RecvError::Lagged(skipped)) => log::warn!("Live feed skipped {} match updates", skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -1,9 +1,33 @@
 mod db;
-mod models;
 mod commands;
+mod events;
+mod windows;
 
 use tauri::Manager;
 
+// Opt-in REST API; settings are read once, so changes apply on the next launch
+async fn start_api(repo: pong_core::Repository) {
+  let config = match pong_api::ApiConfig::load(&repo).await {
+    Ok(config) => config,
+    Err(e) => {
+      log::error!("REST API not started: {}", e);
+      return;
+    }
+  };
+  if !config.enabled {
+    return;
+  }
+  let Some(token) = config.token.clone() else {
+    log::warn!("REST API not started: set {} first", pong_api::config::TOKEN_KEY);
+    return;
+  };
+  tauri::async_runtime::spawn(async move {
+    if let Err(e) = pong_api::serve(repo, config, token).await {
+      log::error!("REST API stopped: {}", e);
+    }
+  });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
@@ -21,8 +45,10 @@ pub fn run() {
       // Initialize Database
       let handle = app.handle().clone();
       tauri::async_runtime::block_on(async move {
-          let pool = db::init_db(&handle).await.expect("Database initialization failed");
-          handle.manage(db::AppState { db: pool });
+          let repo = db::init_db(&handle).await.expect("Database initialization failed");
+          events::forward_live_feed(handle.clone(), &repo);
+          start_api(repo.clone()).await;
+          handle.manage(db::AppState { repo });
       });
 
       Ok(())
@@ -33,22 +59,48 @@ pub fn run() {
         commands::update_user,
         commands::get_game_modes,
         commands::create_game_mode,
+        commands::update_game_mode,
+        commands::archive_game_mode,
+        commands::delete_game_mode,
         commands::ensure_basic_game_mode,
         commands::start_match,
         commands::add_point,
+        commands::record_let,
+        commands::call_timeout,
+        commands::forfeit_match,
         commands::undo_last_point,
+        commands::redo_point,
+        commands::rebuild_match,
+        commands::pause_match,
+        commands::resume_match,
+        commands::edit_match_result,
+        commands::get_match_audit,
         commands::get_match,
         commands::get_user_matches,
         commands::set_first_server,
         commands::cancel_match,
         commands::get_open_matches,
         commands::get_user_statistics,
+        commands::suggest_handicap,
+        commands::create_tournament,
+        commands::get_tournaments,
+        commands::register_tournament_player,
+        commands::start_tournament,
+        commands::get_bracket,
+        commands::start_next_bracket_match,
+        commands::create_league,
+        commands::get_leagues,
+        commands::register_league_player,
+        commands::start_league,
+        commands::get_league,
+        commands::start_league_fixture,
         commands::get_key_bindings,
         commands::set_key_binding,
         commands::delete_key_binding,
         commands::reset_key_bindings,
         commands::get_setting,
-        commands::set_setting
+        commands::set_setting,
+        windows::open_scoreboard_window
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
@@ -0,0 +1,48 @@
+// Scoreboard window for the TV next to the table: borderless, read-only, one match at a time.
+// Opening it again retargets the existing window instead of creating a second one.
+
+use crate::db::AppState;
+use crate::events::{self, AppEvent};
+use pong_core::AppError;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+pub const MAIN_LABEL: &str = "main";
+pub const SCOREBOARD_LABEL: &str = "scoreboard";
+
+// Any monitor other than the one showing the scoring UI
+fn other_monitor(app: &AppHandle) -> Option<tauri::Monitor> {
+    let main = app.get_webview_window(MAIN_LABEL)?.current_monitor().ok().flatten()?;
+    app.available_monitors()
+        .ok()?
+        .into_iter()
+        .find(|m| m.position() != main.position())
+}
+
+#[tauri::command]
+pub async fn open_scoreboard_window(app: AppHandle, state: State<'_, AppState>, match_id: i64) -> Result<(), AppError> {
+    state.repo.get_match(match_id).await?;
+
+    if let Some(window) = app.get_webview_window(SCOREBOARD_LABEL) {
+        events::emit(&app, AppEvent::ScoreboardTarget(match_id));
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let url = WebviewUrl::App(format!("scoreboard/{}", match_id).into());
+    let window = WebviewWindowBuilder::new(&app, SCOREBOARD_LABEL, url)
+        .title("Scoreboard")
+        .decorations(false)
+        .inner_size(1280.0, 720.0)
+        .build()
+        .map_err(|e| {
+            log::error!("Failed to open the scoreboard window: {}", e);
+            AppError::Internal("Could not open the scoreboard window".to_string())
+        })?;
+
+    // With a second screen attached, go fullscreen there; otherwise it stays a window to drag over
+    if let Some(monitor) = other_monitor(&app) {
+        let _ = window.set_position(*monitor.position());
+        let _ = window.set_fullscreen(true);
+    }
+    Ok(())
+}
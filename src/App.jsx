@@ -7,6 +7,7 @@ import { SettingsProvider, useSettings } from '@/contexts/SettingsContext';
 import Dashboard from './components/Dashboard';
 import MatchSetup from './components/MatchSetup/MatchSetup';
 import GameScreen from './components/GameScreen/GameScreen';
+import Scoreboard from './components/Scoreboard';
 import HallOfFame from './components/HallOfFame';
 import PlayerManagement from './components/PlayerManagement';
 import KeyBindingsSettings from './components/KeyBindingsSettings';
@@ -107,7 +108,19 @@ function AppInner() {
   );
 }
 
+// The scoreboard window has no navbar, splash or key bindings: it only shows a match
+const isScoreboardWindow = window.location.pathname.startsWith('/scoreboard/');
+
 function App() {
+  if (isScoreboardWindow) {
+    return (
+      <Router>
+        <Routes>
+          <Route path="/scoreboard/:id" element={<Scoreboard />} />
+        </Routes>
+      </Router>
+    );
+  }
   return (
     <SettingsProvider>
       <AppInner />
@@ -1,13 +1,14 @@
-import React, { useState, useEffect, useMemo } from 'react';
+import React, { useState, useEffect, useMemo, useRef } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
 import { Card } from "@/components/ui/card";
 import { Button as ButtonOriginal } from "@/components/ui/button";
-import { Trophy, RotateCcw, Home, Check, LogOut, Play, Trash2 } from "lucide-react";
+import { Trophy, RotateCcw, Home, Check, LogOut, Play, Trash2, Tv } from "lucide-react";
 import Counter from "@/components/react-bits/Counter";
 
 // Bypass TS checks for JS components
 const Button = ButtonOriginal as any;
 import { matchService } from '@/services/matchService';
+import { eventService } from '@/services/eventService';
 import { getColorTheme, getIconComponent } from "@/lib/gameConfig";
 import { cn } from "@/lib/utils";
 import { useSpatialNav } from '@/hooks/useSpatialNav';
@@ -20,6 +21,8 @@ const GameScreen = () => {
     const [isLoading, setIsLoading] = useState(true);
     const [rematchSwap, setRematchSwap] = useState(true);
     const [showExitDialog, setShowExitDialog] = useState(false);
+    // One key per rematch attempt: a double-fired press starts one match, the next rematch starts another
+    const rematchKey = useRef(crypto.randomUUID());
 
     // Determine nav group based on game state
     const navGroup = useMemo(() => {
@@ -34,7 +37,11 @@ const GameScreen = () => {
 
     const fetchMatch = async () => {
         try {
-            const data = await matchService.getMatch(id);
+            let data = await matchService.getMatch(id);
+            // Coming back to a match left with "save and exit"
+            if (data.isPaused && data.status === 'in_progress') {
+                data = await matchService.resumeMatch(id, data.version);
+            }
             setMatch(data);
         } catch (err) {
             console.error("Error fetching match", err);
@@ -47,31 +54,56 @@ const GameScreen = () => {
         fetchMatch();
     }, [id]);
 
+    // Follow changes made from other windows; an older version never replaces a newer one
+    useEffect(() => {
+        const unlisten = eventService.onMatchUpdated(updated => {
+            if (updated._id !== Number(id)) return;
+            setMatch((current: any) => (!current || updated.version >= current.version ? updated : current));
+        });
+        return () => { unlisten.then(fn => fn()); };
+    }, [id]);
+
+    // Another window (or a faster key press) changed the match first: show its current state
+    const handleActionError = (err: unknown) => {
+        console.error(err);
+        if (matchService.isConflict(err)) fetchMatch();
+    };
+
     const handlePoint = async (playerId: string) => {
         if (!match || match.status === 'finished') return;
         try {
-            const updated = await matchService.addPoint(id, playerId);
+            // Keyed on the version we scored against: a double-fired key event maps to the same action
+            const updated = await matchService.addPoint(id, playerId, null, `add_point:${id}:${match.version}:${playerId}`, match.version);
             setMatch(updated);
         } catch (err) {
-            console.error(err);
+            handleActionError(err);
         }
     };
 
     const handleUndo = async () => {
         try {
-            const updated = await matchService.undoPoint(id);
+            const updated = await matchService.undoPoint(id, `undo:${id}:${match?.version}`, match?.version);
+            setMatch(updated);
+        } catch (err) {
+            handleActionError(err);
+        }
+    };
+
+    const handleRedo = async () => {
+        try {
+            const updated = await matchService.redoPoint(id, `redo:${id}:${match?.version}`, match?.version);
             setMatch(updated);
         } catch (err) {
-            console.error(err);
+            handleActionError(err);
         }
     };
 
     const handleSetFirstServer = async (playerId: string) => {
         try {
-            const updated = await matchService.setFirstServer(id, playerId);
+            const updated = await matchService.setFirstServer(id, playerId, match?.version);
             setMatch(updated);
         } catch (err) {
-            console.error(err);
+            handleActionError(err);
         }
     }
 
@@ -92,6 +124,10 @@ const GameScreen = () => {
         if (match?.events?.length > 0) handleUndo();
     }, [match]);
 
+    useAction('redo', () => {
+        if (match?.redoCount > 0) handleRedo();
+    }, [match]);
+
     useAction('confirm', () => {
         (document.activeElement as HTMLElement)?.click();
     }, []);
@@ -121,10 +157,13 @@ const GameScreen = () => {
                 serveType: match.matchRules?.serveType,
                 servesInDeuce: match.matchRules?.servesInDeuce,
                 player3Id: p3,
-                player4Id: p4
+                player4Id: p4,
+                handicapP1: rematchSwap ? match.matchRules?.handicapP2 : match.matchRules?.handicapP1,
+                handicapP2: rematchSwap ? match.matchRules?.handicapP1 : match.matchRules?.handicapP2
             };
 
-            const newMatch = await matchService.startMatch(p1, p2, match.gameMode.id || match.gameMode._id, overrides);
+            const newMatch = await matchService.startMatch(p1, p2, match.gameMode.id || match.gameMode._id, overrides, rematchKey.current);
+            rematchKey.current = crypto.randomUUID();
             setMatch(null); // Clear current match
             navigate(`/game/${newMatch._id}`);
             // Force reload via key or fetch is handled by useEffect on [id]
@@ -151,8 +190,6 @@ const GameScreen = () => {
     const { player1, player2, player3, player4, score, gameMode, winner } = match;
     const isDoubles = !!player3 && !!player4;
 
-    const totalPoints = score.p1 + score.p2;
-
     const p1Theme = getColorTheme(player1.color || 'blue');
     const p2Theme = getColorTheme(player2.color || 'red');
     const p3Theme = isDoubles ? getColorTheme(player3.color || 'blue') : p1Theme;
@@ -226,68 +263,9 @@ const GameScreen = () => {
         )
     }
 
-    // Determine Server
-    let serverId;
-    const { servesBeforeChange, pointsToWin } = gameMode;
+    // Server is computed by the backend serve engine
+    const serverId = match.currentServerId;
     const matchRules = match.matchRules || {};
-    const servesInDeuce = matchRules.servesInDeuce || 1;
-    const isDeuce = score.p1 >= pointsToWin - 1 && score.p2 >= pointsToWin - 1;
-
-    // Serving Sequence
-    // Singles: [P1, P2] (or [P2, P1] if P2 started? No, normally P1, P2 if P1 starts)
-    // Actually standard logic: Starter, Receiver.
-    // Doubles: [P1, P2, P3, P4] assuming A, X, B, Y.
-    // But who is A/X/B/Y depends on who starts.
-    // Let's assume standard rotation array based on Starter.
-    const starterId = match.firstServer || player1._id;
-
-    // Construct rotation array. P3 is partner of P1. P4 partner of P2.
-    // Order: Captain1 -> Captain2 -> Partner1 -> Partner2
-    // If starter is P1: P1 -> P2 -> P3 -> P4.
-    // If starter is P2: P2 -> P1 -> P4 -> P3.
-    // If starter is P3: P3 -> P4 -> P1 -> P2.
-    // If starter is P4: P4 -> P3 -> P2 -> P1.
-    // We need to find the "Index 0" player.
-
-    let rotationIds = [];
-    if (isDoubles) {
-        // Find which team starts
-        const startTeam1 = starterId === player1._id || starterId === player3._id;
-        if (startTeam1) {
-            // Team 1 starts. A=Starter.
-            // Opponent X? P2 or P4. Let's assume P2 is "Primary Receiver".
-            // A(Start) -> X(P2) -> B(Partner) -> Y(P4).
-            const partner = starterId === player1._id ? player3._id : player1._id;
-            rotationIds = [starterId, player2._id, partner, player4._id];
-        } else {
-            // Team 2 starts. A=Starter.
-            const partner = starterId === player2._id ? player4._id : player2._id;
-            rotationIds = [starterId, player1._id, partner, player3._id];
-        }
-    } else {
-        const receiverId = starterId === player1._id ? player2._id : player1._id;
-        rotationIds = [starterId, receiverId];
-    }
-
-    const pointsBeforeDeuce = (pointsToWin - 1) * 2;
-    let turnIndex = 0;
-
-    if (isDeuce) {
-        const offset = totalPoints - pointsBeforeDeuce;
-        if (offset >= 0) {
-            turnIndex = Math.floor(offset / servesInDeuce);
-        } else {
-            // Edge case
-            turnIndex = Math.floor(totalPoints / servesBeforeChange);
-        }
-    } else {
-        turnIndex = Math.floor(totalPoints / servesBeforeChange);
-    }
-
-    // In Deuce, we continue rotation but step 1 by 1 (or servesInDeuce).
-    // The turnIndex represents "how many service turns have passed".
-    // We map this to the rotation array.
-    serverId = rotationIds[turnIndex % rotationIds.length];
 
 const handleExit = () => {
         if (match?.status === 'in_progress' && !match?.winner) {
@@ -297,14 +275,20 @@ const handleExit = () => {
         }
     };
 
-    const handleSaveAndExit = () => {
+    const handleSaveAndExit = async () => {
         setShowExitDialog(false);
+        try {
+            // Time away from the table doesn't count as playing time
+            await matchService.pauseMatch(id, match?.version);
+        } catch (err) {
+            console.error("Error pausing match", err);
+        }
         navigate('/');
     };
 
     const handleAbandon = async () => {
         try {
-            await matchService.cancelMatch(id);
+            await matchService.cancelMatch(id, match?.version);
         } catch (err) {
             console.error("Failed to abandon match", err);
         }
@@ -342,6 +326,15 @@ const handleExit = () => {
                 >
                     <Home size={12} /> Home
                 </button>
+                <button
+                    data-nav="true"
+                    data-nav-group="game"
+                    tabIndex={0}
+                    onClick={() => matchService.openScoreboardWindow(id).catch(handleActionError)}
+                    className="arcade-btn-cyan px-4 py-2 text-[0.45rem] flex items-center gap-2"
+                >
+                    <Tv size={12} /> Scoreboard
+                </button>
             </div>
 
             {/* Rule Indicators (Cross/Free) */}
@@ -366,6 +359,15 @@ const handleExit = () => {
                     </span>
                 </div>
 
+                {/* GAMES BADGE (Best-of-N only) */}
+                {gameMode.gamesToWin > 1 && match.gamesWon && (
+                    <div className="px-4 py-1 bg-black/70 border border-green-500/20 rounded-lg flex items-center gap-3">
+                        <span className="font-arcade text-neutral-500 uppercase" style={{ fontSize: '0.45rem' }}>Games</span>
+                        <span className="font-black text-green-400">{match.gamesWon.p1} - {match.gamesWon.p2}</span>
+                        <span className="font-arcade text-neutral-600 uppercase" style={{ fontSize: '0.4rem' }}>Best of {gameMode.gamesToWin * 2 - 1}</span>
+                    </div>
+                )}
+
                 {/* DEUCE BADGE (Shows only if active) */}
                 {match.isDeuce && (
                     <div className="animate-in fade-in slide-in-from-top-2 duration-300">
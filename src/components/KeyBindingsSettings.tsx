@@ -86,7 +86,7 @@ const KeyBindingsSettings = () => {
     // Section grouping
     const navActions = grouped.filter(g => g.action.startsWith('nav_'));
     const generalActions = grouped.filter(g => g.action === 'confirm' || g.action === 'back');
-    const gameActions = grouped.filter(g => g.action === 'add_point_left' || g.action === 'add_point_right' || g.action === 'undo');
+    const gameActions = grouped.filter(g => g.action === 'add_point_left' || g.action === 'add_point_right' || g.action === 'undo' || g.action === 'redo');
 
     const renderSection = (title: string, items: GroupedBindings[]) => (
         <div className="space-y-3">
@@ -17,6 +17,7 @@ import { useAction } from '@/hooks/useAction';
 
 type OpenMatch = {
     _id: number;
+    version: number;
     player1: { name: string; color?: string; icon?: string };
     player2: { name: string; color?: string; icon?: string };
     player3?: { name: string } | null;
@@ -56,6 +57,8 @@ const MatchSetup = () => {
     // New Match Config Overrides
     const [serveType, setServeType] = useState('free');
     const [servesInDeuce, setServesInDeuce] = useState(1);
+    const [handicapP1, setHandicapP1] = useState(0);
+    const [handicapP2, setHandicapP2] = useState(0);
 
     // Open matches state
     const [openMatches, setOpenMatches] = useState<OpenMatch[]>([]);
@@ -75,6 +78,8 @@ const MatchSetup = () => {
 
     // Start Button Ref for focus
     const startButtonRef = useRef<HTMLButtonElement>(null);
+    // One key per setup screen: a double-fired start creates a single match
+    const startKey = useRef(crypto.randomUUID());
 
     // User Dialog State
     const [isUserDialogOpen, setIsUserDialogOpen] = useState(false);
@@ -114,7 +119,7 @@ const MatchSetup = () => {
     const handleCancelMatch = async (matchId: number) => {
         setIsCancelling(true);
         try {
-            await matchService.cancelMatch(matchId);
+            await matchService.cancelMatch(matchId, openMatches.find(m => m._id === matchId)?.version);
             setOpenMatches(prev => prev.filter(m => m._id !== matchId));
         } catch (err) {
             console.error("Failed to cancel match", err);
@@ -148,18 +153,31 @@ const MatchSetup = () => {
                 serveType,
                 servesInDeuce,
                 player3Id: isDoubles ? p3 : null,
-                player4Id: isDoubles ? p4 : null
+                player4Id: isDoubles ? p4 : null,
+                handicapP1,
+                handicapP2
             };
-            const startMatchPromise = matchService.startMatch(p1, p2, modeId, overrides);
+            const startMatchPromise = matchService.startMatch(p1, p2, modeId, overrides, startKey.current);
             const [_, match] = await Promise.all([minAnimationTime, startMatchPromise]);
             navigate(`/game/${match._id}`);
         } catch (err) {
             console.error("Failed to start match", err);
-            alert(`Failed to start match: ${err}`);
+            alert(`Failed to start match: ${matchService.errorMessage(err)}`);
             setIsTransitioning(false);
         }
     };
 
+    const handleSuggestHandicap = async () => {
+        if (!p1 || !p2 || !modeId) return;
+        try {
+            const suggestion = await matchService.suggestHandicap(p1, p2, modeId);
+            setHandicapP1(suggestion.p1);
+            setHandicapP2(suggestion.p2);
+        } catch (err) {
+            console.error("Failed to suggest handicap", err);
+        }
+    };
+
     const handleCreateUser = async () => {
         if (!newUserName.trim()) return;
         setCreatingUser(true);
@@ -578,6 +596,23 @@ const MatchSetup = () => {
                                                 <button onClick={() => setServesInDeuce(servesInDeuce + 1)} data-nav="true" data-nav-group="setup-step-2" tabIndex={0} className="w-10 h-10 rounded-lg bg-neutral-900 border border-green-500/20 hover:bg-green-500/10 hover:border-green-500/50 text-green-400 flex items-center justify-center font-bold focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-green-500/50">+</button>
                                             </div>
                                         </div>
+
+                                        {/* Handicap: starting score of each game */}
+                                        <div className="space-y-3 col-span-2">
+                                            <div className="flex items-center justify-between">
+                                                <label className="text-[10px] font-arcade text-neutral-500 uppercase">Handicap Start</label>
+                                                <button onClick={handleSuggestHandicap} data-nav="true" data-nav-group="setup-step-2" tabIndex={0} className="text-[10px] font-arcade text-green-400/70 hover:text-green-300 uppercase focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-green-500/50 rounded px-1">Suggest</button>
+                                            </div>
+                                            <div className="grid grid-cols-2 gap-6">
+                                                {[{ value: handicapP1, set: setHandicapP1 }, { value: handicapP2, set: setHandicapP2 }].map(({ value, set }, idx) => (
+                                                    <div key={idx} className="flex items-center gap-3">
+                                                        <button onClick={() => set(Math.max(0, value - 1))} data-nav="true" data-nav-group="setup-step-2" tabIndex={0} className="w-10 h-10 rounded-lg bg-neutral-900 border border-green-500/20 hover:bg-green-500/10 hover:border-green-500/50 text-green-400 flex items-center justify-center font-bold focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-green-500/50">-</button>
+                                                        <div className="flex-1 text-center font-arcade font-bold text-xl text-green-400">+{value}</div>
+                                                        <button onClick={() => set(value + 1)} data-nav="true" data-nav-group="setup-step-2" tabIndex={0} className="w-10 h-10 rounded-lg bg-neutral-900 border border-green-500/20 hover:bg-green-500/10 hover:border-green-500/50 text-green-400 flex items-center justify-center font-bold focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-green-500/50">+</button>
+                                                    </div>
+                                                ))}
+                                            </div>
+                                        </div>
                                     </div>
                                 </div>
 
@@ -0,0 +1,73 @@
+import { useEffect, useState } from 'react';
+import { useParams } from 'react-router-dom';
+import Counter from "@/components/react-bits/Counter";
+import { matchService } from '@/services/matchService';
+import { eventService } from '@/services/eventService';
+import { getColorTheme } from "@/lib/gameConfig";
+import { cn } from "@/lib/utils";
+
+// Read-only scoreboard for the TV window opened by open_scoreboard_window.
+// Follows the match through backend events and never sends a command that changes it.
+const Scoreboard = () => {
+    const { id } = useParams();
+    const [matchId, setMatchId] = useState(Number(id));
+    const [match, setMatch] = useState<any>(null);
+
+    useEffect(() => {
+        matchService.getMatch(matchId).then(setMatch).catch(err => console.error("Error fetching match", err));
+    }, [matchId]);
+
+    useEffect(() => {
+        const unlisteners = [
+            eventService.onMatchUpdated(updated => {
+                if (updated._id !== matchId) return;
+                setMatch((current: any) => (!current || updated.version >= current.version ? updated : current));
+            }),
+            eventService.onMatchCancelled(cancelledId => {
+                if (cancelledId === matchId) setMatch((current: any) => current && { ...current, status: 'abandoned' });
+            }),
+            eventService.onScoreboardTarget(setMatchId),
+        ];
+        return () => { unlisteners.forEach(u => u.then(fn => fn())); };
+    }, [matchId]);
+
+    if (!match) return <div data-tauri-drag-region className="min-h-screen bg-black" />;
+
+    const { player1, player2, player3, player4, score, winner, courtSides } = match;
+    const teams: Record<string, any> = {
+        p1: { names: [player1, player3], score: score.p1, gamesWon: match.gamesWon.p1, theme: getColorTheme(player1.color || 'blue') },
+        p2: { names: [player2, player4], score: score.p2, gamesWon: match.gamesWon.p2, theme: getColorTheme(player2.color || 'red') },
+    };
+    // Each team is shown on the end of the table it is playing from, so the TV follows side switches
+    const sides = [teams[courtSides?.left ?? 'p1'], teams[courtSides?.right ?? 'p2']];
+    const serverId = match.currentServerId;
+
+    return (
+        // Drag the borderless window anywhere, double-click to maximize
+        <div data-tauri-drag-region className="min-h-screen bg-black text-white flex flex-col items-center justify-center gap-8 font-mono select-none">
+            <div data-tauri-drag-region className="flex w-full justify-around items-center">
+                {sides.map((side, i) => {
+                    const serving = side.names.some(p => p && p._id === serverId) && !winner;
+                    return (
+                        <div key={i} data-tauri-drag-region className="flex flex-col items-center gap-6">
+                            <Counter value={side.score} places={[10, 1]} fontSize={320} padding={0} gap={10} textColor="white" fontWeight={900} gradientHeight={0} />
+                            <div className={cn("text-4xl font-bold px-6 py-2 rounded-xl border-4", side.theme.border, serving && "bg-white/10")}>
+                                {side.names.filter(Boolean).map(p => p.name).join(' + ')}
+                            </div>
+                            {serving && <span className={cn("font-arcade", side.theme.text)} style={{ fontSize: '0.8rem' }}>SERVE</span>}
+                        </div>
+                    );
+                })}
+            </div>
+
+            {match.gameMode.gamesToWin > 1 && (
+                <div className="text-3xl font-black text-green-400">Games {sides[0].gamesWon} - {sides[1].gamesWon}</div>
+            )}
+            {winner && <div className="font-arcade text-yellow-400" style={{ fontSize: '1.2rem' }}>{winner.name} WINS</div>}
+            {match.status === 'abandoned' && <div className="font-arcade text-neutral-500" style={{ fontSize: '1rem' }}>MATCH ABANDONED</div>}
+            {match.isPaused && match.status === 'in_progress' && <div className="font-arcade text-neutral-400" style={{ fontSize: '1rem' }}>PAUSED</div>}
+        </div>
+    );
+};
+
+export default Scoreboard;
@@ -1,5 +1,6 @@
 import { createContext, useContext, useState, useEffect, type ReactNode } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { eventService } from '@/services/eventService';
 
 export type Language = 'en' | 'it';
 
@@ -48,6 +49,17 @@ export function SettingsProvider({ children }: { children: ReactNode }) {
         invoke<string | null>('get_setting', { key: 'language' }).then(val => {
             if (val === 'en' || val === 'it') setLanguageState(val);
         }).catch(() => {});
+
+        // Keep every window on the same theme and language
+        const unlisten = eventService.onSettingChanged((key, value) => {
+            if (key === 'bg_theme') {
+                const found = BG_THEMES.find(t => t.id === value);
+                if (found) setBgThemeState(found);
+            } else if (key === 'language' && (value === 'en' || value === 'it')) {
+                setLanguageState(value);
+            }
+        });
+        return () => { unlisten.then(fn => fn()); };
     }, []);
 
     const setBgTheme = (theme: BgTheme) => {
@@ -0,0 +1,39 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { KeyBinding } from './keyBindingService';
+
+// Emitted by the backend after every change, to every window (see src-tauri/src/events.rs)
+export const APP_EVENTS = {
+    matchUpdated: 'match-updated',
+    matchCancelled: 'match-cancelled',
+    userCreated: 'user-created',
+    userUpdated: 'user-updated',
+    keyBindingChanged: 'key-binding-changed',
+    keyBindingDeleted: 'key-binding-deleted',
+    keyBindingsReset: 'key-bindings-reset',
+    settingChanged: 'setting-changed',
+    scoreboardTarget: 'scoreboard-target',
+} as const;
+
+export const eventService = {
+    // Payload is the full populated match after any change to it, including REST API points
+    onMatchUpdated: (handler: (match: any) => void): Promise<UnlistenFn> =>
+        listen<any>(APP_EVENTS.matchUpdated, e => handler(e.payload)),
+    onMatchCancelled: (handler: (matchId: number) => void): Promise<UnlistenFn> =>
+        listen<{ matchId: number }>(APP_EVENTS.matchCancelled, e => handler(e.payload.matchId)),
+    onUserCreated: (handler: (user: any) => void): Promise<UnlistenFn> =>
+        listen<any>(APP_EVENTS.userCreated, e => handler(e.payload)),
+    onUserUpdated: (handler: (user: any) => void): Promise<UnlistenFn> =>
+        listen<any>(APP_EVENTS.userUpdated, e => handler(e.payload)),
+    onKeyBindingChanged: (handler: (binding: KeyBinding) => void): Promise<UnlistenFn> =>
+        listen<KeyBinding>(APP_EVENTS.keyBindingChanged, e => handler(e.payload)),
+    onKeyBindingDeleted: (handler: (id: number) => void): Promise<UnlistenFn> =>
+        listen<{ id: number }>(APP_EVENTS.keyBindingDeleted, e => handler(e.payload.id)),
+    // Payload is every binding after the reset
+    onKeyBindingsReset: (handler: (bindings: KeyBinding[]) => void): Promise<UnlistenFn> =>
+        listen<KeyBinding[]>(APP_EVENTS.keyBindingsReset, e => handler(e.payload)),
+    onSettingChanged: (handler: (key: string, value: string) => void): Promise<UnlistenFn> =>
+        listen<{ key: string; value: string }>(APP_EVENTS.settingChanged, e => handler(e.payload.key, e.payload.value)),
+    // open_scoreboard_window was called for another match while the scoreboard was open
+    onScoreboardTarget: (handler: (matchId: number) => void): Promise<UnlistenFn> =>
+        listen<{ matchId: number }>(APP_EVENTS.scoreboardTarget, e => handler(e.payload.matchId)),
+};
@@ -6,5 +6,14 @@ export const gameModeService = {
     },
     create: async (data) => {
         return await invoke('create_game_mode', { ...data });
+    },
+    update: async (id, data) => {
+        return await invoke('update_game_mode', { id: Number(id), ...data });
+    },
+    archive: async (id) => {
+        return await invoke('archive_game_mode', { id: Number(id) });
+    },
+    remove: async (id) => {
+        return await invoke('delete_game_mode', { id: Number(id) });
     }
 };
@@ -17,7 +17,8 @@ export type KeyAction =
     | 'back'
     | 'add_point_left'
     | 'add_point_right'
-    | 'undo';
+    | 'undo'
+    | 'redo';
 
 export const ACTION_LABELS: Record<KeyAction, string> = {
     nav_up: 'Navigate Up',
@@ -29,6 +30,7 @@ export const ACTION_LABELS: Record<KeyAction, string> = {
     add_point_left: 'Add Point (Left Team)',
     add_point_right: 'Add Point (Right Team)',
     undo: 'Undo Last Point',
+    redo: 'Redo Undone Point',
 };
 
 export const keyBindingService = {
@@ -0,0 +1,27 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export const leagueService = {
+    // tieBreakers: any order of 'head_to_head' and 'point_differential', applied after wins
+    createLeague: async (name, gameModeId, doubleRound = false, tieBreakers = ['head_to_head', 'point_differential']) => {
+        return await invoke('create_league', { name, gameModeId: Number(gameModeId), doubleRound, tieBreakers });
+    },
+    getLeagues: async () => {
+        return await invoke('get_leagues');
+    },
+    // Only while the league is still in registration
+    registerPlayer: async (leagueId, userId) => {
+        return await invoke('register_league_player', { leagueId: Number(leagueId), userId: Number(userId) });
+    },
+    // Draws every fixture; returns { league, entries, fixtures, standings }
+    startLeague: async (leagueId) => {
+        return await invoke('start_league', { leagueId: Number(leagueId) });
+    },
+    // Fixtures are 'pending', 'in_progress' or 'finished'; standings are in rank order
+    getLeague: async (leagueId) => {
+        return await invoke('get_league', { leagueId: Number(leagueId) });
+    },
+    // Returns the started match; fixtures can be played in any order
+    startFixture: async (fixtureId) => {
+        return await invoke('start_league_fixture', { fixtureId: Number(fixtureId) });
+    },
+};
@@ -1,7 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
 
 export const matchService = {
-    startMatch: async (player1Id, player2Id, gameModeId, overrides = {}) => {
+    startMatch: async (player1Id, player2Id, gameModeId, overrides = {}, idempotencyKey = null) => {
         // Note: overrides are currently ignored by the Rust backend in this simple port
         return await invoke('start_match', { 
             player1Id: Number(player1Id), 
@@ -10,14 +10,48 @@ export const matchService = {
             servesInDeuce: overrides.servesInDeuce ? Number(overrides.servesInDeuce) : null,
             serveType: overrides.serveType || null,
             player3Id: overrides.player3Id ? Number(overrides.player3Id) : null,
-            player4Id: overrides.player4Id ? Number(overrides.player4Id) : null
+            player4Id: overrides.player4Id ? Number(overrides.player4Id) : null,
+            handicapP1: overrides.handicapP1 ? Number(overrides.handicapP1) : null,
+            handicapP2: overrides.handicapP2 ? Number(overrides.handicapP2) : null,
+            idempotencyKey
         });
     },
-    addPoint: async (matchId, playerId) => {
-        return await invoke('add_point', { matchId: Number(matchId), playerId: Number(playerId) });
+    // A repeated idempotency key returns the first response instead of scoring twice;
+    // expectedVersion is the match version the caller saw, a newer one fails with CONFLICT
+    addPoint: async (matchId, playerId, reason = null, idempotencyKey = null, expectedVersion = null) => {
+        return await invoke('add_point', { matchId: Number(matchId), playerId: Number(playerId), reason, idempotencyKey, expectedVersion });
     },
-    undoPoint: async (matchId) => {
-        return await invoke('undo_last_point', { matchId: Number(matchId) });
+    recordLet: async (matchId, expectedVersion = null) => {
+        return await invoke('record_let', { matchId: Number(matchId), expectedVersion });
+    },
+    callTimeout: async (matchId, playerId, expectedVersion = null) => {
+        return await invoke('call_timeout', { matchId: Number(matchId), playerId: Number(playerId), expectedVersion });
+    },
+    // reason: 'walkover', 'retirement' or 'disqualification'; playerId is on the conceding side
+    forfeitMatch: async (matchId, playerId, reason, expectedVersion = null) => {
+        return await invoke('forfeit_match', { matchId: Number(matchId), playerId: Number(playerId), reason, expectedVersion });
+    },
+    undoPoint: async (matchId, idempotencyKey = null, expectedVersion = null) => {
+        return await invoke('undo_last_point', { matchId: Number(matchId), idempotencyKey, expectedVersion });
+    },
+    redoPoint: async (matchId, idempotencyKey = null, expectedVersion = null) => {
+        return await invoke('redo_point', { matchId: Number(matchId), idempotencyKey, expectedVersion });
+    },
+    rebuildMatch: async (matchId) => {
+        return await invoke('rebuild_match', { id: Number(matchId) });
+    },
+    pauseMatch: async (matchId, expectedVersion = null) => {
+        return await invoke('pause_match', { id: Number(matchId), expectedVersion });
+    },
+    resumeMatch: async (matchId, expectedVersion = null) => {
+        return await invoke('resume_match', { id: Number(matchId), expectedVersion });
+    },
+    // Corrects a finished match from its game scores ([{ p1, p2 }, ...]) or a full event list
+    editMatchResult: async (matchId, { games = null, events = null, note = null } = {}, expectedVersion = null) => {
+        return await invoke('edit_match_result', { matchId: Number(matchId), games, events, note, expectedVersion });
+    },
+    getMatchAudit: async (matchId) => {
+        return await invoke('get_match_audit', { matchId: Number(matchId) });
     },
     getMatch: async (matchId) => {
         return await invoke('get_match', { id: Number(matchId) });
@@ -25,14 +59,26 @@ export const matchService = {
     getUserMatches: async (userId) => {
         return await invoke('get_user_matches', { userId: Number(userId) });
     },
-    cancelMatch: async (matchId) => {
-        return await invoke('cancel_match', { id: Number(matchId) });
+    cancelMatch: async (matchId, expectedVersion = null) => {
+        return await invoke('cancel_match', { id: Number(matchId), expectedVersion });
     },
-    setFirstServer: async (matchId, playerId) => {
+    setFirstServer: async (matchId, playerId, expectedVersion = null) => {
         // Rust expects first_server_id as firstServerId (camelCase)
-        return await invoke('set_first_server', { id: Number(matchId), firstServerId: Number(playerId) });
+        return await invoke('set_first_server', { id: Number(matchId), firstServerId: Number(playerId), expectedVersion });
+    },
+    // Returns { p1, p2 } starting scores based on the players' history
+    suggestHandicap: async (player1Id, player2Id, gameModeId) => {
+        return await invoke('suggest_handicap', { player1Id: Number(player1Id), player2Id: Number(player2Id), gameModeId: Number(gameModeId) });
+    },
+    // Borderless read-only window for a TV; if it is already open it switches to this match
+    openScoreboardWindow: async (matchId) => {
+        return await invoke('open_scoreboard_window', { matchId: Number(matchId) });
     },
     getOpenMatches: async () => {
         return await invoke('get_open_matches');
-    }
+    },
+    // Commands fail with { code, message, reason? }; CONFLICT means the write used a stale copy of the match.
+    // Every match command takes an optional expectedVersion, the version of the match the caller last saw
+    isConflict: (err) => err?.code === 'CONFLICT',
+    errorMessage: (err) => err?.message ?? String(err)
 };
@@ -0,0 +1,26 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export const tournamentService = {
+    // format: 'single_elimination' | 'double_elimination'; the reset only applies to double
+    createTournament: async (name, gameModeId, format = 'single_elimination', grandFinalReset = true) => {
+        return await invoke('create_tournament', { name, gameModeId: Number(gameModeId), format, grandFinalReset });
+    },
+    getTournaments: async () => {
+        return await invoke('get_tournaments');
+    },
+    // Only while the tournament is still in registration
+    registerPlayer: async (tournamentId, userId) => {
+        return await invoke('register_tournament_player', { tournamentId: Number(tournamentId), userId: Number(userId) });
+    },
+    // Seeds by win rate and draws the bracket; returns { tournament, entries, matches }
+    startTournament: async (tournamentId) => {
+        return await invoke('start_tournament', { tournamentId: Number(tournamentId) });
+    },
+    getBracket: async (tournamentId) => {
+        return await invoke('get_bracket', { tournamentId: Number(tournamentId) });
+    },
+    // Returns the started match; the winner advances automatically when it finishes
+    startNextMatch: async (tournamentId) => {
+        return await invoke('start_next_bracket_match', { tournamentId: Number(tournamentId) });
+    },
+};